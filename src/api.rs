@@ -0,0 +1,183 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::MinesweeperError;
+use crate::parsers::parser::ApiData;
+
+pub const DEFAULT_BASE_URL: &str = "https://api.greev.eu/v2/stats/minesweeper/game";
+
+/// Downloads the game data of played games by their GameID.
+#[derive(Clone, Debug)]
+pub struct ApiClient {
+    /// The GameID gets appended to this URL.
+    pub base_url: String,
+    pub timeout: Duration,
+    /// How often a failed request is repeated, the delay doubles after every attempt.
+    pub retries: u32,
+    pub retry_delay: Duration,
+    /// Fetched games are stored in this directory and reused on later fetches.
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl Default for ApiClient {
+    fn default() -> Self {
+        ApiClient {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            timeout: Duration::from_secs(10),
+            retries: 3,
+            retry_delay: Duration::from_millis(500),
+            cache_dir: None,
+        }
+    }
+}
+
+impl ApiClient {
+    /// Returns the raw game data of `game_id`, from the cache if possible.
+    pub fn fetch(&self, game_id: &str) -> Result<String, MinesweeperError> {
+        let game_id = game_id.trim();
+        // The id ends up in the URL and the cache file name
+        if game_id.is_empty()
+            || !game_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(MinesweeperError::InvalidGameId(game_id.to_string()));
+        }
+
+        let cache_file = self.cache_file(game_id);
+        if let Some(data) = cache_file
+            .as_ref()
+            .and_then(|file| fs::read_to_string(file).ok())
+        {
+            return Ok(data);
+        }
+
+        let response = self.request(game_id)?;
+        let api_data: ApiData = serde_json::from_str(&response)
+            .map_err(|error| MinesweeperError::InvalidApiResponse(game_id.to_string(), error))?;
+
+        if let Some(file) = cache_file {
+            // A failed cache write must not fail the fetch itself
+            let _ = write_atomic(&file, &api_data.game_data);
+        }
+
+        Ok(api_data.game_data)
+    }
+
+    /// Games are cached per server, so a mirror never serves the games of another one.
+    fn cache_file(&self, game_id: &str) -> Option<PathBuf> {
+        let server: String = self
+            .base_url
+            .split_once("://")
+            .map_or(self.base_url.as_str(), |(_, rest)| rest)
+            .trim_end_matches('/')
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        self.cache_dir
+            .as_ref()
+            .map(|dir| dir.join(server).join(format!("{game_id}.txt")))
+    }
+
+    /// Requests the game, repeating timeouts, connection problems and server errors.
+    fn request(&self, game_id: &str) -> Result<String, MinesweeperError> {
+        let agent = ureq::AgentBuilder::new().timeout(self.timeout).build();
+        let url = format!("{}/{game_id}", self.base_url.trim_end_matches('/'));
+        let fetch_error = |reason: String| MinesweeperError::Fetch(game_id.to_string(), reason);
+
+        let mut delay = self.retry_delay;
+        let mut attempt = 0;
+        loop {
+            let error = match agent.get(&url).call() {
+                Ok(response) => {
+                    return response
+                        .into_string()
+                        .map_err(|error| fetch_error(error.to_string()))
+                }
+                Err(ureq::Error::Status(404, _)) => {
+                    return Err(MinesweeperError::GameNotFound(game_id.to_string()))
+                }
+                Err(ureq::Error::Status(status, _)) if status != 429 && status < 500 => {
+                    return Err(fetch_error(format!(
+                        "server responded with status {status}"
+                    )))
+                }
+                Err(ureq::Error::Status(status, _)) => {
+                    format!("server responded with status {status}")
+                }
+                Err(error) => error.to_string(),
+            };
+
+            if attempt >= self.retries {
+                return Err(fetch_error(error));
+            }
+            attempt += 1;
+            thread::sleep(delay);
+            delay *= 2;
+        }
+    }
+}
+
+/// Writes into a temporary file that is renamed, so an interrupted write never leaves a partial
+/// file at `path`.
+fn write_atomic(path: &Path, data: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let temporary = path.with_extension(format!("tmp-{}", process::id()));
+    let result = fs::write(&temporary, data).and_then(|_| fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caches_games_per_server() {
+        let client = |base_url: &str| ApiClient {
+            base_url: base_url.to_string(),
+            cache_dir: Some(PathBuf::from("cache")),
+            ..ApiClient::default()
+        };
+
+        assert_eq!(
+            client(DEFAULT_BASE_URL).cache_file("abc"),
+            Some(PathBuf::from(
+                "cache/api.greev.eu_v2_stats_minesweeper_game/abc.txt"
+            ))
+        );
+        assert_eq!(
+            client("http://localhost:8080/game/").cache_file("abc"),
+            Some(PathBuf::from("cache/localhost_8080_game/abc.txt"))
+        );
+        assert_eq!(ApiClient::default().cache_file("abc"), None);
+    }
+
+    #[test]
+    fn writes_cache_files_atomically() {
+        let dir = std::env::temp_dir().join(format!("minesweeper_cache_{}", process::id()));
+        let file = dir.join("server").join("abc.txt");
+
+        write_atomic(&file, "2=4x2,50+00++").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "2=4x2,50+00++");
+        assert_eq!(fs::read_dir(file.parent().unwrap()).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
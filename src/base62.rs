@@ -1,23 +1,145 @@
-const BASE: i64 = 62;
-const CHARACTERS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+use crate::error::Base62Error;
+
+const BASE: u64 = 62;
+const CHARACTERS: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Decodes a base62 number.
+pub fn decode(number: &str) -> Result<i64, Base62Error> {
+    if number.is_empty() {
+        return Err(Base62Error::Empty);
+    }
 
-pub fn decode(number: &str) -> i64 {
     let mut result: i64 = 0;
-    let length = number.len();
-    let chars: Vec<char> = CHARACTERS.chars().collect();
-
-    for i in 0..length {
-        let digit = chars
-            .iter()
-            .position(|&c| {
-                c == number
-                    .chars()
-                    .nth(length - i - 1)
-                    .expect("Error while decoding base62")
+
+    for (position, character) in number.chars().enumerate() {
+        let digit = digit(character).ok_or(Base62Error::InvalidCharacter {
+            character,
+            position,
+        })?;
+        result = result
+            .checked_mul(BASE as i64)
+            .and_then(|result| result.checked_add(digit))
+            .ok_or_else(|| Base62Error::Overflow(number.to_string()))?;
+    }
+
+    Ok(result)
+}
+
+/// Encodes a number into its shortest base62 representation.
+pub fn encode(mut number: u64) -> String {
+    let mut result = Vec::new();
+
+    loop {
+        result.push(CHARACTERS[(number % BASE) as usize]);
+        number /= BASE;
+        if number == 0 {
+            break;
+        }
+    }
+
+    result.iter().rev().map(|&c| c as char).collect()
+}
+
+/// Decodes the first `width` characters of `data` and returns the value together with the rest
+/// of the input.
+pub fn decode_fixed(data: &str, width: usize) -> Result<(i64, &str), Base62Error> {
+    let split = data
+        .char_indices()
+        .nth(width)
+        .map(|(index, _)| index)
+        .unwrap_or(data.len());
+    let (number, rest) = data.split_at(split);
+
+    let found = number.chars().count();
+    if found != width {
+        return Err(Base62Error::TooShort {
+            expected: width,
+            found,
+        });
+    }
+
+    Ok((decode(number)?, rest))
+}
+
+/// Encodes a number into exactly `width` characters, padded with leading zeros.
+pub fn encode_fixed(number: u64, width: usize) -> Result<String, Base62Error> {
+    let encoded = encode(number);
+
+    if encoded.len() > width {
+        return Err(Base62Error::DoesNotFit { number, width });
+    }
+
+    Ok(format!("{}{encoded}", "0".repeat(width - encoded.len())))
+}
+
+fn digit(character: char) -> Option<i64> {
+    let value = match character {
+        '0'..='9' => character as u8 - b'0',
+        'A'..='Z' => character as u8 - b'A' + 10,
+        'a'..='z' => character as u8 - b'a' + 36,
+        _ => return None,
+    };
+
+    Some(value as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_digits_and_letters() {
+        assert_eq!(decode("0"), Ok(0));
+        assert_eq!(decode("z"), Ok(61));
+        assert_eq!(decode("10"), Ok(62));
+        assert_eq!(decode("1A"), Ok(72));
+    }
+
+    #[test]
+    fn rejects_empty_and_invalid_input() {
+        assert_eq!(decode(""), Err(Base62Error::Empty));
+        assert_eq!(
+            decode("1-"),
+            Err(Base62Error::InvalidCharacter {
+                character: '-',
+                position: 1
             })
-            .expect("Error while decoding base62") as i64;
-        result += BASE.pow(i as u32) * digit;
+        );
     }
 
-    result
+    #[test]
+    fn rejects_overflow() {
+        let max = encode(i64::MAX as u64);
+        assert_eq!(decode(&max), Ok(i64::MAX));
+
+        let too_large = format!("{max}0");
+        assert_eq!(decode(&too_large), Err(Base62Error::Overflow(too_large)));
+    }
+
+    #[test]
+    fn encode_round_trips() {
+        for number in [0, 1, 61, 62, 3843, 3844, i64::MAX as u64] {
+            assert_eq!(decode(&encode(number)), Ok(number as i64));
+        }
+    }
+
+    #[test]
+    fn fixed_width_round_trips() {
+        assert_eq!(encode_fixed(5, 3).as_deref(), Ok("005"));
+        assert_eq!(decode_fixed("005rest", 3), Ok((5, "rest")));
+        assert_eq!(
+            encode_fixed(62, 1),
+            Err(Base62Error::DoesNotFit {
+                number: 62,
+                width: 1
+            })
+        );
+        assert_eq!(
+            decode_fixed("0", 2),
+            Err(Base62Error::TooShort {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
 }
@@ -1,11 +1,125 @@
+use std::fmt;
+
+use serde::Serialize;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum MinesweeperError {
     #[error("Gif Encoding Error")]
     GifEncoding,
+    #[error("A {width}x{height} pixel GIF is too large, at most 65535x65535 pixels are supported")]
+    GifTooLarge { width: u32, height: u32 },
     #[error("Image insertion Error")]
     ImageInsertion,
     #[error("Image Save Error")]
     ImageSave,
+    #[error("Unable to fetch game {0}: {1}")]
+    Fetch(String, String),
+    #[error("Game {0} not found")]
+    GameNotFound(String),
+    #[error("Invalid GameID '{0}'")]
+    InvalidGameId(String),
+    #[error("Invalid API response for game {0}: {1}")]
+    InvalidApiResponse(String, #[source] serde_json::Error),
+    #[error("Unable to import {format} replay: {reason}")]
+    Import { format: String, reason: String },
+    #[error("Unable to export {format}: {reason}")]
+    Export { format: String, reason: String },
+    #[error("Version {to} game does not match the original version {from} game")]
+    Mismatch { from: String, to: String },
+    #[error("Unable to write JSON: {0}")]
+    JsonWrite(#[source] serde_json::Error),
+    #[error("Unable to read input from {0}: {1}")]
+    InputRead(String, #[source] std::io::Error),
+    #[error("Unable to create output {0}: {1}")]
+    OutputCreate(String, #[source] std::io::Error),
+    #[error("Unable to write output: {0}")]
+    Write(#[source] std::io::Error),
+    #[error("Unable to start a batch worker: {0}")]
+    WorkerSpawn(#[source] std::io::Error),
+    #[error("{0} cannot be animated, use gif or render an image")]
+    NotAnimatable(String),
+    #[error("Unknown output format for {0}, expected png, jpeg, webp or gif")]
+    UnknownOutputFormat(String),
+    #[error("Unable to load textures from {0}: {1}")]
+    TextureLoad(String, #[source] std::io::Error),
+    #[error("Invalid textures: {0}")]
+    InvalidTextures(String),
+    #[error("Unable to get Version")]
+    MissingVersion,
+    #[error("Unknown / Unsupported version: {version} (supported: {})", supported.join(", "))]
+    UnsupportedVersion {
+        version: String,
+        supported: Vec<String>,
+    },
+    #[error("Missing {0} section")]
+    MissingSection(Section),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("Unable to serialize {section} entry {index}: {reason}")]
+    Serialize {
+        section: Section,
+        index: usize,
+        reason: String,
+    },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Base62Error {
+    #[error("empty number")]
+    Empty,
+    #[error("invalid character '{character}' at position {position}")]
+    InvalidCharacter { character: char, position: usize },
+    #[error("number '{0}' is too large")]
+    Overflow(String),
+    #[error("expected {expected} characters but found {found}")]
+    TooShort { expected: usize, found: usize },
+    #[error("{number} does not fit into {width} characters")]
+    DoesNotFit { number: u64, width: usize },
+}
+
+/// The part of a game string a [`ParseError`] occurred in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Metadata,
+    Mines,
+    Opens,
+    Flags,
+    Chords,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Section::Metadata => "metadata",
+            Section::Mines => "mines",
+            Section::Opens => "opens",
+            Section::Flags => "flags",
+            Section::Chords => "chords",
+        })
+    }
+}
+
+/// A single entry of a game string that could not be parsed.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unable to parse {section} entry {index} ('{entry}'): {reason}")]
+pub struct ParseError {
+    pub section: Section,
+    /// Index of the `;` separated entry inside its section.
+    pub index: usize,
+    /// The offending substring.
+    pub entry: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    pub fn new(section: Section, index: usize, entry: &str, reason: impl Into<String>) -> Self {
+        ParseError {
+            section,
+            index,
+            entry: entry.to_string(),
+            reason: reason.into(),
+        }
+    }
 }
@@ -0,0 +1,56 @@
+use crate::error::MinesweeperError;
+use crate::exporters::json::JsonExporter;
+use crate::exporters::rawvf::RawVfExporter;
+use crate::parsers::parser::ParsedData;
+
+/// Writes a [`ParsedData`] in the format of another Minesweeper client or tool.
+pub trait Iexporter {
+    /// Name the format is selected by, in lowercase.
+    fn name(&self) -> &str;
+    fn export(&self, data: &ParsedData) -> Result<String, MinesweeperError>;
+}
+
+/// Owns the available exporters and picks one by name.
+pub struct ExporterRegistry {
+    exporters: Vec<Box<dyn Iexporter>>,
+}
+
+impl Default for ExporterRegistry {
+    /// Creates a registry containing all built-in exporters.
+    fn default() -> Self {
+        let mut registry = ExporterRegistry::new();
+        registry.register(Box::new(RawVfExporter));
+        registry.register(Box::new(JsonExporter));
+        registry
+    }
+}
+
+impl ExporterRegistry {
+    /// Creates a registry without any exporters.
+    pub fn new() -> Self {
+        ExporterRegistry {
+            exporters: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, exporter: Box<dyn Iexporter>) {
+        self.exporters.push(exporter);
+    }
+
+    pub fn supported_formats(&self) -> Vec<String> {
+        self.exporters
+            .iter()
+            .map(|e| e.name().to_string())
+            .collect()
+    }
+
+    pub fn find_exporter(&self, name: &str) -> Option<&dyn Iexporter> {
+        let name = name.to_lowercase();
+
+        self.exporters
+            .iter()
+            .rev()
+            .find(|e| e.name() == name)
+            .map(|e| e.as_ref())
+    }
+}
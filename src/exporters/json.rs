@@ -0,0 +1,17 @@
+use crate::error::MinesweeperError;
+use crate::exporters::exporter::Iexporter;
+use crate::json::JsonGame;
+use crate::parsers::parser::ParsedData;
+
+/// Writes games in the schema of [`crate::json`].
+pub struct JsonExporter;
+
+impl Iexporter for JsonExporter {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn export(&self, data: &ParsedData) -> Result<String, MinesweeperError> {
+        serde_json::to_string_pretty(&JsonGame::from(data)).map_err(MinesweeperError::JsonWrite)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod exporter;
+pub mod json;
+pub mod rawvf;
@@ -0,0 +1,154 @@
+//! Exporter for the RAW video format (rawvf) read by community replay tools.
+//!
+//! The file is a list of `Key: value` header lines followed by the board, one line per row with
+//! `*` for mines and `0` for safe fields, and the mouse events. Every event is written as
+//! `<seconds> <event> <column> <row> (<x> <y>)` with columns and rows starting at 1 and the
+//! position in pixels of 16 pixel fields.
+
+use std::fmt::Display;
+
+use crate::error::MinesweeperError;
+use crate::exporters::exporter::Iexporter;
+use crate::grid::Coord;
+use crate::minesweeper_logic::GameOutcome;
+use crate::parsers::parser::{Action, ParsedData};
+use crate::replay::{played_actions, ReplayAction};
+use crate::statistics::Statistics;
+
+const RAWVF_VERSION: &str = "Rev5";
+/// Size of a field in pixels.
+const FIELD_SIZE: i32 = 16;
+
+pub struct RawVfExporter;
+
+impl Iexporter for RawVfExporter {
+    fn name(&self) -> &str {
+        "rawvf"
+    }
+
+    fn export(&self, data: &ParsedData) -> Result<String, MinesweeperError> {
+        let metadata = &data.metadata;
+        let board = &data.game_board;
+        let statistics = Statistics::of(data);
+        // Analysers expect the events to end with the game
+        let actions = played_actions(data);
+        if let Some(action) = actions.iter().find(|action| action.tick() < 0) {
+            return Err(MinesweeperError::Export {
+                format: self.name().to_string(),
+                reason: format!("negative time on tick {}", action.tick()),
+            });
+        }
+        let marks = data
+            .flag_data
+            .iter()
+            .any(|flag| matches!(flag.action, Action::Toggle));
+
+        let mut raw = String::new();
+        let mut line = |key: &str, value: &dyn Display| {
+            raw.push_str(&format!("{key}: {value}\n"));
+        };
+        line("RawVF_Version", &RAWVF_VERSION);
+        line("Program", &env!("CARGO_PKG_NAME"));
+        line("Version", &env!("CARGO_PKG_VERSION"));
+        line(
+            "Level",
+            &level(metadata.x_size, metadata.y_size, board.mine_count),
+        );
+        line("Width", &metadata.x_size);
+        line("Height", &metadata.y_size);
+        line("Mines", &board.mine_count);
+        line("Marks", &if marks { "On" } else { "Off" });
+        line("Time", &seconds(statistics.time_ms));
+        line("BBBV", &statistics.bbbv);
+
+        raw.push_str("Board:\n");
+        for y in 0..board.fields.height() {
+            for x in 0..board.fields.width() {
+                let field = &board.fields[Coord::new(x, y)];
+                raw.push(if field.mine { '*' } else { '0' });
+            }
+            raw.push('\n');
+        }
+
+        raw.push_str("Events:\n");
+        raw.push_str("0.000 start\n");
+        for action in &actions {
+            let time_ms = metadata.ticks_to_ms(action.tick());
+            let (x, y, events) = match action {
+                ReplayAction::Open(open) => (open.x, open.y, ["lc", "lr"]),
+                ReplayAction::Flag(flag) => (flag.x, flag.y, ["rc", "rr"]),
+                ReplayAction::Chord(chord) => (chord.x, chord.y, ["mc", "mr"]),
+            };
+            for event in ["mv", events[0], events[1]] {
+                raw.push_str(&format!(
+                    "{} {event} {} {} ({} {})\n",
+                    seconds(time_ms),
+                    x + 1,
+                    y + 1,
+                    x * FIELD_SIZE + FIELD_SIZE / 2,
+                    y * FIELD_SIZE + FIELD_SIZE / 2
+                ));
+            }
+        }
+
+        let end = match GameOutcome::of(data) {
+            GameOutcome::Won => Some("won"),
+            GameOutcome::Lost { .. } => Some("blast"),
+            GameOutcome::Abandoned => None,
+        };
+        if let Some(end) = end {
+            raw.push_str(&format!("{} {end}\n", seconds(statistics.time_ms)));
+        }
+
+        Ok(raw.trim_end().to_string())
+    }
+}
+
+fn level(x_size: i32, y_size: i32, mines: u32) -> &'static str {
+    match (x_size, y_size, mines) {
+        (8, 8, 10) | (9, 9, 10) => "Beginner",
+        (16, 16, 40) => "Intermediate",
+        (30, 16, 99) => "Expert",
+        _ => "Custom",
+    }
+}
+
+fn seconds(time_ms: i64) -> String {
+    format!("{}.{:03}", time_ms / 1000, time_ms % 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::parser::OpenAction;
+    use crate::parsers::registry::ParserRegistry;
+
+    #[test]
+    fn ends_events_with_the_game() {
+        // Won on tick 2, the click on the mine afterwards is not exported
+        let data = ParserRegistry::default()
+            .parse("2=4x1,50+00+301;20z+")
+            .unwrap();
+        let raw = RawVfExporter.export(&data).unwrap();
+
+        assert!(raw.contains("Time: 0.050\n"), "{raw}");
+        assert!(raw.ends_with("0.050 lr 4 1 (56 8)\n0.050 won"), "{raw}");
+        assert!(!raw.contains("blast"), "{raw}");
+    }
+
+    #[test]
+    fn rejects_negative_times() {
+        let mut data = ParserRegistry::default().parse("2=4x1,50+00+301+").unwrap();
+        data.open_data = vec![OpenAction {
+            x: 3,
+            y: 0,
+            time: -61,
+            total_time: -61,
+        }];
+
+        assert!(matches!(
+            RawVfExporter.export(&data),
+            Err(MinesweeperError::Export { .. })
+        ));
+    }
+}
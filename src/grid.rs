@@ -0,0 +1,117 @@
+use std::ops::{Index, IndexMut};
+
+use serde::{Deserialize, Serialize};
+
+/// A position on the board, `x` is the column and `y` the row.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Coord {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Coord {
+    pub fn new(x: usize, y: usize) -> Self {
+        Coord { x, y }
+    }
+}
+
+/// A `width` x `height` grid of cells stored row by row.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Sets every cell to `value`.
+    pub fn fill(&mut self, value: T) {
+        self.cells.fill(value);
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.x < self.width && coord.y < self.height
+    }
+
+    /// Converts signed coordinates into a [`Coord`] if they are on the grid.
+    pub fn coord(&self, x: i64, y: i64) -> Option<Coord> {
+        let coord = Coord::new(usize::try_from(x).ok()?, usize::try_from(y).ok()?);
+        self.contains(coord).then_some(coord)
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.contains(coord)
+            .then(|| &self.cells[coord.y * self.width + coord.x])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        if self.contains(coord) {
+            Some(&mut self.cells[coord.y * self.width + coord.x])
+        } else {
+            None
+        }
+    }
+
+    /// The up to eight cells surrounding `coord`.
+    pub fn neighbours(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        (-1..=1_i64)
+            .flat_map(|yd| (-1..=1_i64).map(move |xd| (xd, yd)))
+            .filter(|&(xd, yd)| xd != 0 || yd != 0)
+            .filter_map(move |(xd, yd)| self.coord(coord.x as i64 + xd, coord.y as i64 + yd))
+    }
+
+    /// All coordinates row by row.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coord::new(x, y)))
+    }
+
+    /// All cells together with their coordinate, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        assert!(
+            self.contains(coord),
+            "{coord:?} is outside of the {}x{} grid",
+            self.width,
+            self.height
+        );
+        &self.cells[coord.y * self.width + coord.x]
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        assert!(
+            self.contains(coord),
+            "{coord:?} is outside of the {}x{} grid",
+            self.width,
+            self.height
+        );
+        &mut self.cells[coord.y * self.width + coord.x]
+    }
+}
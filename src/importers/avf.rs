@@ -0,0 +1,189 @@
+//! Importer for the `.avf` replays of Minesweeper Arbiter.
+//!
+//! Layout of the parts that are read, everything else is skipped:
+//!
+//! | Bytes    | Content                                                                   |
+//! |----------|---------------------------------------------------------------------------|
+//! | 0        | format version                                                            |
+//! | 1..5     | unused                                                                    |
+//! | 5        | mode: 3 beginner, 4 intermediate, 5 expert, 6 custom                      |
+//! | custom   | width - 1, height - 1 and the mine count as big endian u16                |
+//! | 2 / mine | row and column of every mine, starting at 1                               |
+//! | `[..]`   | text header with date, level and player                                   |
+//! | 8 / event| mouse events until the first byte of a record is no event type            |
+//!
+//! A mouse event is `type, x high, hundredths, x low, seconds low, y high, seconds high, y low`.
+//! The position is in pixels from the top left corner of the board with 16 pixels per field.
+
+use crate::error::MinesweeperError;
+use crate::grid::Coord;
+use crate::importers::events::{into_parsed_data, Button, MouseAction, MouseEvent};
+use crate::importers::importer::Iimporter;
+use crate::importers::reader::ByteReader;
+use crate::parsers::parser::{Metadata, ParsedData};
+
+/// Size of a field in pixels.
+const FIELD_SIZE: usize = 16;
+const EVENT_SIZE: usize = 8;
+
+pub struct AvfImporter;
+
+impl Iimporter for AvfImporter {
+    fn name(&self) -> &str {
+        "AVF"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["avf"]
+    }
+
+    fn import(&self, data: &[u8]) -> Result<ParsedData, MinesweeperError> {
+        read_avf(data).map_err(|reason| MinesweeperError::Import {
+            format: self.name().to_string(),
+            reason,
+        })
+    }
+}
+
+fn read_avf(data: &[u8]) -> Result<ParsedData, String> {
+    let mut reader = ByteReader::new(data);
+    reader.skip(5)?;
+
+    let (x_size, y_size, mine_count) = match reader.u8()? {
+        3 => (8, 8, 10),
+        4 => (16, 16, 40),
+        5 => (30, 16, 99),
+        6 => {
+            let width = reader.u8()? as usize + 1;
+            let height = reader.u8()? as usize + 1;
+            (width, height, reader.u16_be()? as usize)
+        }
+        mode => return Err(format!("unknown mode {mode}")),
+    };
+
+    let mut mines = Vec::with_capacity(mine_count);
+    for index in 0..mine_count {
+        let row = reader.u8()? as usize;
+        let column = reader.u8()? as usize;
+        if !(1..=y_size).contains(&row) || !(1..=x_size).contains(&column) {
+            return Err(format!(
+                "mine {index} at row {row} column {column} is outside of the board"
+            ));
+        }
+        mines.push(Coord::new(column - 1, row - 1));
+    }
+
+    reader.skip_past(b'[')?;
+    reader.skip_past(b']')?;
+
+    let mut events = Vec::new();
+    while let Ok(record) = reader.bytes(EVENT_SIZE) {
+        let kind = record[0];
+        // The footer with the skin and statistics follows the last event
+        if kind & 1 == 0 {
+            break;
+        }
+
+        let x = u16::from_be_bytes([record[1], record[3]]) as usize / FIELD_SIZE;
+        let y = u16::from_be_bytes([record[5], record[7]]) as usize / FIELD_SIZE;
+        let coord = (x < x_size && y < y_size).then(|| Coord::new(x, y));
+        let seconds = u16::from_be_bytes([record[6], record[4]]) as i64;
+        let time_ms = seconds * 1000 + record[2] as i64 * 10;
+
+        for (bit, action) in [
+            (0x02, MouseAction::Press(Button::Left)),
+            (0x04, MouseAction::Release(Button::Left)),
+            (0x08, MouseAction::Press(Button::Right)),
+            (0x10, MouseAction::Release(Button::Right)),
+            (0x20, MouseAction::Press(Button::Middle)),
+            (0x40, MouseAction::Release(Button::Middle)),
+        ] {
+            if kind & bit != 0 {
+                events.push(MouseEvent {
+                    action,
+                    coord,
+                    time_ms,
+                });
+            }
+        }
+    }
+
+    let metadata = Metadata {
+        x_size: x_size as i32,
+        y_size: y_size as i32,
+        // Arbiter measures in hundredths of a second
+        timeunits: 10,
+    };
+
+    Ok(into_parsed_data(metadata, &mines, &[], &events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minesweeper_logic::GameOutcome;
+    use crate::parsers::parser::OpenAction;
+
+    /// A mouse event on the field `x`|`y` at `seconds` and `hundredths`.
+    fn event(kind: u8, x: u16, y: u16, seconds: u16, hundredths: u8) -> [u8; EVENT_SIZE] {
+        let [x_high, x_low] = (x * FIELD_SIZE as u16 + 8).to_be_bytes();
+        let [y_high, y_low] = (y * FIELD_SIZE as u16 + 8).to_be_bytes();
+        let [seconds_high, seconds_low] = seconds.to_be_bytes();
+        [
+            kind,
+            x_high,
+            hundredths,
+            x_low,
+            seconds_low,
+            y_high,
+            seconds_high,
+            y_low,
+        ]
+    }
+
+    /// A custom 4x2 game with a mine in the top left corner.
+    fn avf(mine: [u8; 2], events: &[[u8; EVENT_SIZE]]) -> Vec<u8> {
+        let mut data = vec![0, 0, 0, 0, 0, 6, 3, 1, 0, 1];
+        data.extend(mine);
+        data.extend(b"[0|date|player]");
+        data.extend(events.concat());
+        // Footer
+        data.extend([0; EVENT_SIZE]);
+        data
+    }
+
+    #[test]
+    fn imports_custom_game() {
+        let data = avf([1, 1], &[event(0x03, 3, 1, 0, 50), event(0x05, 3, 1, 1, 0)]);
+        let parsed = AvfImporter.import(&data).unwrap();
+
+        assert_eq!((parsed.metadata.x_size, parsed.metadata.y_size), (4, 2));
+        assert!(parsed.game_board.fields[Coord::new(0, 0)].mine);
+        assert_eq!(parsed.game_board.mine_count, 1);
+        assert_eq!(
+            parsed.open_data,
+            [OpenAction {
+                x: 3,
+                y: 1,
+                time: 50,
+                total_time: 50,
+            }]
+        );
+        // 0|1 is only next to numbers and stays closed
+        assert_eq!(GameOutcome::of(&parsed), GameOutcome::Abandoned);
+    }
+
+    #[test]
+    fn rejects_mine_outside_of_board() {
+        let error = AvfImporter.import(&avf([3, 1], &[])).unwrap_err();
+        assert!(
+            error.to_string().contains("outside of the board"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        assert!(AvfImporter.import(&[0, 0, 0, 0, 0, 6, 3]).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::grid::Coord;
+use crate::minesweeper_logic::{Board, FieldState};
+use crate::parsers::parser::{Action, FlagAction, Metadata, OpenAction, ParsedData};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Button {
+    Left,
+    Right,
+    Middle,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MouseAction {
+    Press(Button),
+    Release(Button),
+}
+
+/// A mouse button event of a recorded game.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MouseEvent {
+    pub action: MouseAction,
+    /// `None` if the cursor was outside of the board.
+    pub coord: Option<Coord>,
+    /// Milliseconds since the start of the recording.
+    pub time_ms: i64,
+}
+
+/// Turns raw mouse events into open, flag and chord actions like the original client did.
+///
+/// Fields are opened when the left button is released and flagged when the right button is
+/// pressed. Holding both buttons or releasing the middle button chords. Actions without an
+/// effect in the client, like opening a flag, are dropped, as is everything after the game ended.
+/// `preflags` were placed before the game started and become flag actions on the first tick.
+pub(crate) fn into_parsed_data(
+    metadata: Metadata,
+    mines: &[Coord],
+    preflags: &[Coord],
+    events: &[MouseEvent],
+) -> ParsedData {
+    let game_board = Board::new(&metadata, mines);
+    let mut board = game_board.clone();
+    let mut actions = Actions::new(metadata.timeunits as i64);
+    let start = events.first().map_or(0, |event| event.time_ms);
+
+    for &coord in preflags {
+        if board.fields[coord].field_state == FieldState::Closed {
+            actions.flag(&mut board, coord, 0);
+        }
+    }
+
+    let mut left = false;
+    let mut right = false;
+    // Both buttons were held, the next release chords and the one after does nothing
+    let mut chording = false;
+
+    for event in events {
+        if board.exploded.is_some() || board.is_solved() {
+            break;
+        }
+        let time = event.time_ms - start;
+
+        match event.action {
+            MouseAction::Press(Button::Left) => {
+                left = true;
+                chording |= right;
+            }
+            MouseAction::Press(Button::Right) => {
+                right = true;
+                if left {
+                    chording = true;
+                } else if let Some(coord) = event.coord {
+                    actions.flag(&mut board, coord, time);
+                }
+            }
+            MouseAction::Release(button @ (Button::Left | Button::Right)) => {
+                let other_held = match button {
+                    Button::Left => {
+                        left = false;
+                        right
+                    }
+                    _ => {
+                        right = false;
+                        left
+                    }
+                };
+
+                if let Some(coord) = event.coord {
+                    if chording && other_held {
+                        actions.chord(&mut board, coord, time);
+                    } else if !chording && button == Button::Left {
+                        actions.open(&mut board, coord, time);
+                    }
+                }
+                if !other_held {
+                    chording = false;
+                }
+            }
+            MouseAction::Press(Button::Middle) => {}
+            MouseAction::Release(Button::Middle) => {
+                if let Some(coord) = event.coord {
+                    actions.chord(&mut board, coord, time);
+                }
+            }
+        }
+    }
+
+    ParsedData {
+        metadata,
+        game_board,
+        open_data: actions.opens,
+        flag_data: actions.flags,
+        chord_data: actions.chords,
+    }
+}
+
+/// The actions collected so far, applied to the board as they get added.
+struct Actions {
+    /// Milliseconds per tick.
+    timeunits: i64,
+    opens: Vec<OpenAction>,
+    flags: Vec<FlagAction>,
+    chords: Vec<OpenAction>,
+}
+
+impl Actions {
+    fn new(timeunits: i64) -> Self {
+        Actions {
+            timeunits: timeunits.max(1),
+            opens: Vec::new(),
+            flags: Vec::new(),
+            chords: Vec::new(),
+        }
+    }
+
+    fn open(&mut self, board: &mut Board, coord: Coord, time_ms: i64) {
+        if board.fields[coord].field_state != FieldState::Closed {
+            return;
+        }
+
+        self.opens
+            .push(open_action(&self.opens, coord, time_ms / self.timeunits));
+        board.open_field(coord);
+    }
+
+    fn chord(&mut self, board: &mut Board, coord: Coord, time_ms: i64) {
+        if board.fields[coord].field_state != FieldState::Open {
+            return;
+        }
+
+        self.chords
+            .push(open_action(&self.chords, coord, time_ms / self.timeunits));
+        board.chord_field(coord);
+    }
+
+    fn flag(&mut self, board: &mut Board, coord: Coord, time_ms: i64) {
+        let action = match board.fields[coord].field_state {
+            FieldState::Closed => Action::Place,
+            FieldState::Flagged | FieldState::UnsureFlagged => Action::Remove,
+            FieldState::Open => return,
+        };
+
+        let total_time = time_ms / self.timeunits;
+        let flag = FlagAction {
+            x: coord.x as i32,
+            y: coord.y as i32,
+            time: total_time - self.flags.last().map_or(0, |f| f.total_time),
+            action,
+            total_time,
+        };
+        flag.perform_action(board);
+        self.flags.push(flag);
+    }
+}
+
+fn open_action(previous: &[OpenAction], coord: Coord, total_time: i64) -> OpenAction {
+    OpenAction {
+        x: coord.x as i32,
+        y: coord.y as i32,
+        time: total_time - previous.last().map_or(0, |o| o.total_time),
+        total_time,
+    }
+}
@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use crate::error::MinesweeperError;
+use crate::importers::avf::AvfImporter;
+use crate::importers::json::JsonImporter;
+use crate::importers::rmv::RmvImporter;
+use crate::parsers::parser::ParsedData;
+
+/// Reads the replay files of another Minesweeper client.
+pub trait Iimporter {
+    /// Name of the format used in error messages.
+    fn name(&self) -> &str;
+    /// File extensions without the dot, in lowercase.
+    fn extensions(&self) -> Vec<&str>;
+    fn import(&self, data: &[u8]) -> Result<ParsedData, MinesweeperError>;
+}
+
+/// Owns the available importers and picks one by file extension.
+pub struct ImporterRegistry {
+    importers: Vec<Box<dyn Iimporter>>,
+}
+
+impl Default for ImporterRegistry {
+    /// Creates a registry containing all built-in importers.
+    fn default() -> Self {
+        let mut registry = ImporterRegistry::new();
+        registry.register(Box::new(AvfImporter));
+        registry.register(Box::new(RmvImporter));
+        registry.register(Box::new(JsonImporter));
+        registry
+    }
+}
+
+impl ImporterRegistry {
+    /// Creates a registry without any importers.
+    pub fn new() -> Self {
+        ImporterRegistry {
+            importers: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, importer: Box<dyn Iimporter>) {
+        self.importers.push(importer);
+    }
+
+    /// The importer for the extension of `path`, `None` for game data strings.
+    pub fn find_importer(&self, path: &Path) -> Option<&dyn Iimporter> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+
+        self.importers
+            .iter()
+            .rev()
+            .find(|i| i.extensions().contains(&extension.as_str()))
+            .map(|i| i.as_ref())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::error::MinesweeperError;
+use crate::importers::importer::Iimporter;
+use crate::json::JsonGame;
+use crate::parsers::parser::ParsedData;
+
+/// Reads games in the schema of [`crate::json`].
+pub struct JsonImporter;
+
+impl Iimporter for JsonImporter {
+    fn name(&self) -> &str {
+        "JSON"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["json"]
+    }
+
+    fn import(&self, data: &[u8]) -> Result<ParsedData, MinesweeperError> {
+        let import_error = |reason: String| MinesweeperError::Import {
+            format: self.name().to_string(),
+            reason,
+        };
+
+        serde_json::from_slice::<JsonGame>(data)
+            .map_err(|error| import_error(error.to_string()))?
+            .into_parsed_data()
+            .map_err(import_error)
+    }
+}
@@ -0,0 +1,6 @@
+pub mod avf;
+mod events;
+pub mod importer;
+pub mod json;
+mod reader;
+pub mod rmv;
@@ -0,0 +1,56 @@
+/// Reads the binary replay formats byte by byte, running out of data is an error.
+pub(crate) struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        ByteReader { data, position: 0 }
+    }
+
+    pub fn bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self.position.saturating_add(count);
+        let bytes = self.data.get(self.position..end).ok_or_else(|| {
+            format!(
+                "unexpected end of file at byte {}, expected {count} more bytes",
+                self.position
+            )
+        })?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    pub fn skip(&mut self, count: usize) -> Result<(), String> {
+        self.bytes(count).map(|_| ())
+    }
+
+    pub fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn u16_be(&mut self) -> Result<u16, String> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u24_be(&mut self) -> Result<u32, String> {
+        let bytes = self.bytes(3)?;
+        Ok(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    pub fn u32_be(&mut self) -> Result<u32, String> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Skips everything up to and including `byte`.
+    pub fn skip_past(&mut self, byte: u8) -> Result<(), String> {
+        let offset = self.data[self.position.min(self.data.len())..]
+            .iter()
+            .position(|&b| b == byte)
+            .ok_or_else(|| format!("missing '{}' after byte {}", byte as char, self.position))?;
+        self.position += offset + 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+//! Importer for the `.rmv` replays of Viennasweeper.
+//!
+//! All numbers are big endian. The file starts with the magic `*rmv`, the format version and the
+//! sizes of the following sections:
+//!
+//! | Size | Section                                                              |
+//! |------|----------------------------------------------------------------------|
+//! | u16  | result string, skipped                                               |
+//! | u16  | version info, skipped                                                |
+//! | u16  | player info, skipped                                                 |
+//! | u16  | board: width, height, mine count as u16 and x, y of every mine       |
+//! | u16  | preflags: count as u16 and x, y of every flag placed before the game |
+//! | u16  | properties, skipped                                                  |
+//! | u32  | events                                                               |
+//! | u16  | checksum, skipped                                                    |
+//!
+//! Every event starts with its type. Mouse events (1 to 7) continue with the time in milliseconds
+//! as u24, an unused byte and the x and y position in pixels of the window as u16. Board events
+//! carry the x and y of a field, type 0 has four bytes of unknown meaning and types 15 to 17 end
+//! the game.
+
+use crate::error::MinesweeperError;
+use crate::grid::Coord;
+use crate::importers::events::{into_parsed_data, Button, MouseAction, MouseEvent};
+use crate::importers::importer::Iimporter;
+use crate::importers::reader::ByteReader;
+use crate::parsers::parser::{Metadata, ParsedData};
+
+const MAGIC: &[u8] = b"*rmv";
+/// Size of a field in pixels.
+const FIELD_SIZE: i32 = 16;
+/// Position of the top left corner of the board in the window.
+const BOARD_OFFSET: (i32, i32) = (12, 56);
+
+pub struct RmvImporter;
+
+impl Iimporter for RmvImporter {
+    fn name(&self) -> &str {
+        "RMV"
+    }
+
+    fn extensions(&self) -> Vec<&str> {
+        vec!["rmv"]
+    }
+
+    fn import(&self, data: &[u8]) -> Result<ParsedData, MinesweeperError> {
+        read_rmv(data).map_err(|reason| MinesweeperError::Import {
+            format: self.name().to_string(),
+            reason,
+        })
+    }
+}
+
+struct SectionSizes {
+    result: usize,
+    version: usize,
+    player: usize,
+    board: usize,
+    preflags: usize,
+    properties: usize,
+    events: usize,
+}
+
+fn read_rmv(data: &[u8]) -> Result<ParsedData, String> {
+    let mut reader = ByteReader::new(data);
+    if reader.bytes(MAGIC.len())? != MAGIC {
+        return Err("missing *rmv signature".to_string());
+    }
+
+    let version = reader.u16_be()?;
+    if version != 1 {
+        return Err(format!("unsupported format version {version}"));
+    }
+    let sizes = SectionSizes {
+        result: reader.u16_be()? as usize,
+        version: reader.u16_be()? as usize,
+        player: reader.u16_be()? as usize,
+        board: reader.u16_be()? as usize,
+        preflags: reader.u16_be()? as usize,
+        properties: reader.u16_be()? as usize,
+        events: reader.u32_be()? as usize,
+    };
+    // Checksum size, the checksum follows the events
+    reader.skip(2)?;
+
+    reader.skip(sizes.result + sizes.version + sizes.player)?;
+
+    let mut board = ByteReader::new(reader.bytes(sizes.board)?);
+    let x_size = board.u8()? as usize;
+    let y_size = board.u8()? as usize;
+    if x_size == 0 || y_size == 0 {
+        return Err(format!("invalid board size {x_size}x{y_size}"));
+    }
+    let mine_count = board.u16_be()? as usize;
+    let field = |reader: &mut ByteReader, what: &str, index: usize| {
+        let x = reader.u8()? as usize;
+        let y = reader.u8()? as usize;
+        if x >= x_size || y >= y_size {
+            return Err(format!("{what} {index} at {x}|{y} is outside of the board"));
+        }
+        Ok(Coord::new(x, y))
+    };
+    let mines = (0..mine_count)
+        .map(|index| field(&mut board, "mine", index))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut preflags = Vec::new();
+    if sizes.preflags > 0 {
+        let mut reader = ByteReader::new(reader.bytes(sizes.preflags)?);
+        for index in 0..reader.u16_be()? as usize {
+            preflags.push(field(&mut reader, "preflag", index)?);
+        }
+    }
+
+    reader.skip(sizes.properties)?;
+
+    let events = read_events(ByteReader::new(reader.bytes(sizes.events)?), x_size, y_size)?;
+
+    let metadata = Metadata {
+        x_size: x_size as i32,
+        y_size: y_size as i32,
+        // Viennasweeper measures in milliseconds
+        timeunits: 1,
+    };
+
+    Ok(into_parsed_data(metadata, &mines, &preflags, &events))
+}
+
+fn read_events(
+    mut reader: ByteReader,
+    x_size: usize,
+    y_size: usize,
+) -> Result<Vec<MouseEvent>, String> {
+    let mut events = Vec::new();
+
+    // Games that were not finished lack the end event
+    while let Ok(kind) = reader.u8() {
+        let action = match kind {
+            0 => {
+                reader.skip(4)?;
+                continue;
+            }
+            // Mouse movement
+            1 => None,
+            2 => Some(MouseAction::Press(Button::Left)),
+            3 => Some(MouseAction::Release(Button::Left)),
+            4 => Some(MouseAction::Press(Button::Right)),
+            5 => Some(MouseAction::Release(Button::Right)),
+            6 => Some(MouseAction::Press(Button::Middle)),
+            7 => Some(MouseAction::Release(Button::Middle)),
+            // Changes of the board, they follow from the mouse events
+            9..=14 | 18..=27 => {
+                reader.skip(2)?;
+                continue;
+            }
+            15..=17 => break,
+            kind => return Err(format!("unknown event type {kind}")),
+        };
+
+        let time_ms = reader.u24_be()? as i64;
+        reader.skip(1)?;
+        let x = (reader.u16_be()? as i32 - BOARD_OFFSET.0).div_euclid(FIELD_SIZE);
+        let y = (reader.u16_be()? as i32 - BOARD_OFFSET.1).div_euclid(FIELD_SIZE);
+
+        if let Some(action) = action {
+            let on_board = (0..x_size as i32).contains(&x) && (0..y_size as i32).contains(&y);
+            events.push(MouseEvent {
+                action,
+                coord: on_board.then(|| Coord::new(x as usize, y as usize)),
+                time_ms,
+            });
+        }
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::parser::{Action, FlagAction, OpenAction};
+
+    /// A mouse event on the field `x`|`y` at `time_ms`.
+    fn event(kind: u8, x: i32, y: i32, time_ms: u32) -> Vec<u8> {
+        let mut event = vec![kind];
+        event.extend(&time_ms.to_be_bytes()[1..]);
+        event.push(0);
+        event.extend((BOARD_OFFSET.0 + x * FIELD_SIZE + 8).to_be_bytes()[2..].iter());
+        event.extend((BOARD_OFFSET.1 + y * FIELD_SIZE + 8).to_be_bytes()[2..].iter());
+        event
+    }
+
+    /// A 4x2 game with a mine in the top left corner that got flagged before the start.
+    fn rmv(version: u16, events: &[u8]) -> Vec<u8> {
+        let board = [4, 2, 0, 1, 0, 0];
+        let preflags = [0, 1, 0, 0];
+        let mut data = MAGIC.to_vec();
+        data.extend(version.to_be_bytes());
+        for size in [1, 0, 0, board.len(), preflags.len(), 0] {
+            data.extend((size as u16).to_be_bytes());
+        }
+        data.extend((events.len() as u32).to_be_bytes());
+        data.extend([0, 0]);
+        data.push(b'W');
+        data.extend(board);
+        data.extend(preflags);
+        data.extend(events);
+        data
+    }
+
+    #[test]
+    fn imports_game_with_preflags() {
+        let events = [event(2, 3, 1, 100), event(3, 3, 1, 300), vec![15]].concat();
+        let parsed = RmvImporter.import(&rmv(1, &events)).unwrap();
+
+        assert_eq!((parsed.metadata.x_size, parsed.metadata.y_size), (4, 2));
+        assert_eq!(parsed.game_board.mine_count, 1);
+        assert_eq!(
+            parsed.flag_data,
+            [FlagAction {
+                x: 0,
+                y: 0,
+                time: 0,
+                action: Action::Place,
+                total_time: 0,
+            }]
+        );
+        assert_eq!(
+            parsed.open_data,
+            [OpenAction {
+                x: 3,
+                y: 1,
+                time: 200,
+                total_time: 200,
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_missing_signature() {
+        let mut data = rmv(1, &[]);
+        data[0] = b'#';
+        let error = RmvImporter.import(&data).unwrap_err();
+        assert!(error.to_string().contains("signature"), "{error}");
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let error = RmvImporter.import(&rmv(2, &[])).unwrap_err();
+        assert!(error.to_string().contains("version 2"), "{error}");
+    }
+}
@@ -0,0 +1,153 @@
+//! A JSON representation of a game for tools that do not want to parse game data strings.
+//!
+//! ```json
+//! {
+//!   "schema": 1,
+//!   "metadata": { "x_size": 8, "y_size": 8, "timeunits": 10 },
+//!   "mines": [{ "x": 2, "y": 0 }],
+//!   "opens": [{ "x": 0, "y": 0, "time": 12, "total_time": 12, "time_ms": 120 }],
+//!   "flags": [{ "x": 2, "y": 0, "time": 50, "action": "place", "total_time": 50, "time_ms": 500 }],
+//!   "chords": []
+//! }
+//! ```
+//!
+//! - `metadata`: board size in fields and the length of a tick in milliseconds.
+//! - `mines`: every mine once, `x` is the column and `y` the row, both starting at 0.
+//! - `opens`, `flags` and `chords`: the actions of each kind in the order they happened. `time`
+//!   is the number of ticks since the previous action of the same kind and `total_time` the
+//!   number of ticks since the start of the game. `time_ms` is `total_time` in milliseconds, it is
+//!   only written for convenience and ignored when reading.
+//! - `action` of a flag is `place`, `remove` or `toggle` (question mark).
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::grid::Coord;
+use crate::minesweeper_logic::Board;
+use crate::parsers::parser::{FlagAction, Metadata, OpenAction, ParsedData, MAX_FIELDS};
+
+/// Version of the schema, increased on incompatible changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsonGame {
+    pub schema: u32,
+    pub metadata: Metadata,
+    pub mines: Vec<Coord>,
+    pub opens: Vec<JsonAction<OpenAction>>,
+    pub flags: Vec<JsonAction<FlagAction>>,
+    pub chords: Vec<JsonAction<OpenAction>>,
+}
+
+/// An action together with its time in milliseconds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsonAction<A> {
+    #[serde(flatten)]
+    pub action: A,
+    #[serde(skip_deserializing)]
+    pub time_ms: i64,
+}
+
+impl From<&ParsedData> for JsonGame {
+    fn from(data: &ParsedData) -> Self {
+        let with_time = |total_time: i64| data.metadata.ticks_to_ms(total_time);
+
+        JsonGame {
+            schema: SCHEMA_VERSION,
+            metadata: data.metadata.clone(),
+            mines: data
+                .game_board
+                .fields
+                .iter()
+                .filter(|(_, field)| field.mine)
+                .map(|(coord, _)| coord)
+                .collect(),
+            opens: data
+                .open_data
+                .iter()
+                .map(|open| JsonAction {
+                    action: open.clone(),
+                    time_ms: with_time(open.total_time),
+                })
+                .collect(),
+            flags: data
+                .flag_data
+                .iter()
+                .map(|flag| JsonAction {
+                    action: flag.clone(),
+                    time_ms: with_time(flag.total_time),
+                })
+                .collect(),
+            chords: data
+                .chord_data
+                .iter()
+                .map(|chord| JsonAction {
+                    action: chord.clone(),
+                    time_ms: with_time(chord.total_time),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl JsonGame {
+    /// Checks the game and builds its board, the error describes the first problem found.
+    pub fn into_parsed_data(self) -> Result<ParsedData, String> {
+        if self.schema != SCHEMA_VERSION {
+            return Err(format!(
+                "unsupported schema {}, expected {SCHEMA_VERSION}",
+                self.schema
+            ));
+        }
+
+        let metadata = self.metadata;
+        if metadata.x_size <= 0 || metadata.y_size <= 0 {
+            return Err(format!(
+                "invalid board size {}x{}",
+                metadata.x_size, metadata.y_size
+            ));
+        }
+        if metadata.x_size as i64 * metadata.y_size as i64 > MAX_FIELDS {
+            return Err(format!("board has more than {MAX_FIELDS} fields"));
+        }
+        if metadata.timeunits <= 0 {
+            return Err(format!("invalid timeunits {}", metadata.timeunits));
+        }
+
+        let on_board =
+            |x: usize, y: usize| x < metadata.x_size as usize && y < metadata.y_size as usize;
+        let mut mines = HashSet::new();
+        for mine in &self.mines {
+            if !on_board(mine.x, mine.y) {
+                return Err(format!(
+                    "mine {}|{} is outside of the board",
+                    mine.x, mine.y
+                ));
+            }
+            if !mines.insert(mine) {
+                return Err(format!("mine {}|{} is listed twice", mine.x, mine.y));
+            }
+        }
+
+        let actions = self
+            .opens
+            .iter()
+            .chain(&self.chords)
+            .map(|open| (open.action.x, open.action.y))
+            .chain(self.flags.iter().map(|flag| (flag.action.x, flag.action.y)));
+        for (x, y) in actions {
+            if x < 0 || y < 0 || !on_board(x as usize, y as usize) {
+                return Err(format!("action {x}|{y} is outside of the board"));
+            }
+        }
+
+        Ok(ParsedData {
+            game_board: Board::new(&metadata, &self.mines),
+            metadata,
+            open_data: self.opens.into_iter().map(|open| open.action).collect(),
+            flag_data: self.flags.into_iter().map(|flag| flag.action).collect(),
+            chord_data: self.chords.into_iter().map(|chord| chord.action).collect(),
+        })
+    }
+}
@@ -0,0 +1,45 @@
+//! Parse, replay and render Minesweeper games stored in the greev.eu game data format.
+//!
+//! A game string is parsed with a [`ParserRegistry`] into [`ParsedData`], which can then be
+//! replayed tick by tick with a [`Replay`] or rendered into an image or GIF with a [`Renderer`].
+
+pub mod api;
+pub mod base62;
+pub mod error;
+pub mod exporters;
+pub mod grid;
+pub mod importers;
+pub mod json;
+pub mod minesweeper_logic;
+pub mod minify;
+pub mod parsers;
+pub mod probability;
+pub mod renderer;
+pub mod replay;
+pub mod solver;
+pub mod statistics;
+pub mod textures;
+pub mod upgrade;
+pub mod validation;
+
+pub use api::ApiClient;
+pub use error::{MinesweeperError, ParseError, Section};
+pub use exporters::exporter::{ExporterRegistry, Iexporter};
+pub use grid::{Coord, Grid};
+pub use importers::importer::{Iimporter, ImporterRegistry};
+pub use json::JsonGame;
+pub use minesweeper_logic::{Board, Field, FieldState, GameOutcome};
+pub use minify::{minify, Minified};
+pub use parsers::parser::{
+    Action, ActionType, FlagAction, Iparser, Metadata, OpenAction, ParsedData, RawGame,
+};
+pub use parsers::registry::{ParserRegistry, SerializerRegistry};
+pub use parsers::serializer::Iserializer;
+pub use probability::{mine_probabilities, Probabilities};
+pub use renderer::{OutputFormat, RenderType, Renderer};
+pub use replay::{Replay, ReplayAction, ReplaySnapshot, ReplayState};
+pub use solver::{ClickAnalysis, ClickKind};
+pub use statistics::Statistics;
+pub use textures::load_textures;
+pub use upgrade::upgrade_v1;
+pub use validation::{validate, validate_parsed, Issue, Severity};
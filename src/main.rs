@@ -1,94 +1,799 @@
-use std::io;
-
-use clap::Parser;
-
-use crate::error::MinesweeperError;
-use crate::parsers::parser::{ApiData, Iparser, ParsedData};
-use crate::renderer::{RenderOptions, RenderType, Renderer};
-use crate::textures::load_textures;
-
-mod base62;
-mod error;
-mod minesweeper_logic;
-mod parsers;
-mod renderer;
-mod textures;
-
-fn main() {
-    let args = RenderOptions::parse();
-    let data = fetch_data(&args);
-    let option = data.split_once('=').expect("Unable to get Version");
-    let possible_parsers: Vec<&dyn Iparser> = vec![
-        &parsers::v1::parser::ParserV1,
-        &parsers::v2::parser::ParserV2,
-    ];
-
-    let found_parser = possible_parsers
-        .iter()
-        .find(|p| p.supported_versions().contains(&option.0))
-        .expect("Unknown / Unsupported version");
-
-    let split: Vec<&str> = option.1.split('+').collect();
-
-    let metadata = found_parser.parse_meta_data(split[0].trim());
-
-    let parsed_data = ParsedData {
-        game_board: found_parser.parse_mine_data(split[1].trim(), &metadata),
-        open_data: found_parser.parse_open_data(split[2].trim()),
-        flag_data: found_parser.parse_flag_data(split[3].trim()),
-        metadata,
+use std::any::Any;
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand};
+
+use minesweeper_image::replay::actions_in_order;
+use minesweeper_image::solver;
+use minesweeper_image::{
+    load_textures, minify, upgrade_v1, validate, validate_parsed, ApiClient, ClickAnalysis,
+    ClickKind, ExporterRegistry, GameOutcome, ImporterRegistry, MinesweeperError, OutputFormat,
+    ParsedData, ParserRegistry, RenderType, Renderer, SerializerRegistry, Severity, Statistics,
+};
+
+/// Stack size of the batch workers, the same as the main thread gets on Linux.
+const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(Parser)]
+#[command(
+    about = "Render Minesweeper games into images or GIFs and inspect their data.",
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+    // Without a subcommand the game is rendered
+    #[command(flatten)]
+    render: RenderOptions,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Render a game into an image or GIF.
+    Render(RenderOptions),
+    /// Print the board size, mine count, number of actions and duration of a game.
+    Info(InputOptions),
+    /// Print the statistics of a game.
+    Stats(StatsOptions),
+    /// Check a game for problems.
+    Validate(InputOptions),
+    /// Write a game in another format version.
+    Convert(ConvertOptions),
+    /// Re-encode a game in the shortest form of its version.
+    Minify(RewriteOptions),
+    /// Rewrite version 1 games as version 2, one game per line.
+    Upgrade(RewriteOptions),
+    /// Download the raw data of a game without rendering it.
+    Fetch(FetchOptions),
+    /// Render many games in parallel.
+    Batch(BatchOptions),
+}
+
+/// Where the game data comes from.
+#[derive(Args)]
+pub struct InputOptions {
+    #[arg(
+        short,
+        long,
+        help = "Enable this if you want to insert data yourself, stdin is then read as raw data instead of a GameID."
+    )]
+    custom_input: bool,
+    #[arg(long, conflicts_with_all = ["data", "input_file"], help = "The GameID of the game to fetch.")]
+    game_id: Option<String>,
+    #[arg(long, conflicts_with = "input_file", help = "The raw game data.")]
+    data: Option<String>,
+    #[arg(
+        long,
+        help = "Read the raw game data from this file, .avf and .rmv replays are imported."
+    )]
+    input_file: Option<String>,
+    #[command(flatten)]
+    api: ApiOptions,
+}
+
+#[derive(Args)]
+pub struct RenderOptions {
+    #[arg(
+        short,
+        long,
+        help = "Path to write the render to, '-' writes to stdout. Defaults to output.gif or output.webp."
+    )]
+    output: Option<String>,
+    #[command(flatten)]
+    input: InputOptions,
+    #[command(flatten)]
+    style: StyleOptions,
+}
+
+#[derive(Args)]
+pub struct StatsOptions {
+    #[arg(
+        long,
+        value_enum,
+        default_value = "text",
+        help = "Print the statistics as 'text' or 'json'."
+    )]
+    format: StatsFormat,
+    #[arg(
+        long,
+        help = "Also print whether every click was forced, a guess or a blunder."
+    )]
+    clicks: bool,
+    #[command(flatten)]
+    input: InputOptions,
+}
+
+#[derive(Args)]
+pub struct ConvertOptions {
+    #[arg(
+        long,
+        default_value = "2",
+        help = "The format version to write, 'rawvf' for the RAW video format of community tools or 'json'."
+    )]
+    to: String,
+    #[arg(
+        short,
+        long,
+        default_value = "-",
+        help = "Path to write the converted game to, '-' writes to stdout."
+    )]
+    output: String,
+    #[command(flatten)]
+    input: InputOptions,
+}
+
+/// Options of the commands that rewrite game strings.
+#[derive(Args)]
+pub struct RewriteOptions {
+    #[arg(
+        short,
+        long,
+        default_value = "-",
+        help = "Path to write the result to, '-' writes to stdout."
+    )]
+    output: String,
+    #[command(flatten)]
+    input: InputOptions,
+}
+
+#[derive(Args)]
+pub struct FetchOptions {
+    #[arg(help = "The GameID of the game to fetch.")]
+    game_id: String,
+    #[arg(
+        short,
+        long,
+        default_value = "-",
+        help = "Path to save the raw game data to, '-' writes to stdout."
+    )]
+    output: String,
+    #[command(flatten)]
+    api: ApiOptions,
+}
+
+#[derive(Args)]
+pub struct BatchOptions {
+    #[arg(
+        help = "A file with one GameID or raw game string per line, or a directory of files with raw game data."
+    )]
+    input: PathBuf,
+    #[arg(long, default_value = ".", help = "Directory to write the renders to.")]
+    output_dir: PathBuf,
+    #[arg(
+        short,
+        long,
+        help = "Number of games rendered at the same time. Defaults to the number of CPUs."
+    )]
+    jobs: Option<usize>,
+    #[command(flatten)]
+    style: StyleOptions,
+    #[command(flatten)]
+    api: ApiOptions,
+}
+
+/// Where and how games are fetched by their GameID.
+#[derive(Args)]
+pub struct ApiOptions {
+    #[arg(
+        long,
+        default_value = minesweeper_image::api::DEFAULT_BASE_URL,
+        help = "URL the GameID gets appended to when fetching a game."
+    )]
+    api_url: String,
+    #[arg(
+        long,
+        default_value_t = 10,
+        help = "Seconds to wait for the API before giving up."
+    )]
+    timeout: u64,
+    #[arg(
+        long,
+        default_value_t = 3,
+        help = "How often a failed request is retried."
+    )]
+    retries: u32,
+    #[arg(
+        long,
+        help = "Directory to cache fetched games in, cached games are not fetched again."
+    )]
+    cache_dir: Option<PathBuf>,
+}
+
+impl ApiOptions {
+    fn client(&self) -> ApiClient {
+        ApiClient {
+            base_url: self.api_url.clone(),
+            timeout: Duration::from_secs(self.timeout),
+            retries: self.retries,
+            cache_dir: self.cache_dir.clone(),
+            ..ApiClient::default()
+        }
+    }
+}
+
+/// How a game gets rendered.
+#[derive(Args)]
+pub struct StyleOptions {
+    #[arg(
+        short,
+        long,
+        value_enum,
+        help = "Choose either 'image' or 'gif' to force that type to be generated. If not set, it will choose automatically based on the size."
+    )]
+    force_type: Option<RenderType>,
+    #[arg(
+        long,
+        value_enum,
+        help = "Choose 'png', 'jpeg', 'webp' or 'gif'. If not set, it is inferred from the output extension."
+    )]
+    format: Option<OutputFormat>,
+    #[arg(
+        long,
+        help = "To render the GIF or Image with a custom texture set the path relativ to the executable"
+    )]
+    custom_textures: Option<String>,
+    #[arg(short, long, help = "Should the GIF repeat?")]
+    repeat: bool,
+    #[arg(
+        long,
+        help = "Outline guessed and blundered clicks in the rendered output."
+    )]
+    highlight_guesses: bool,
+    #[arg(
+        long,
+        help = "Colour closed fields by their chance of being a mine in the rendered output."
+    )]
+    heatmap: bool,
+}
+
+#[derive(Copy, Clone)]
+enum StatsFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for StatsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_ref() {
+            "text" => Ok(StatsFormat::Text),
+            "json" => Ok(StatsFormat::Json),
+            _ => Err(format!("Unknown stats format: {}", s)),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command.unwrap_or(Command::Render(cli.render)) {
+        Command::Render(options) => run_render(options),
+        Command::Info(options) => run_info(&options),
+        Command::Stats(options) => run_stats(&options),
+        Command::Validate(options) => run_validate(&options),
+        Command::Convert(options) => run_convert(&options),
+        Command::Minify(options) => run_minify(&options),
+        Command::Upgrade(options) => run_upgrade(&options),
+        Command::Fetch(options) => run_fetch(&options),
+        Command::Batch(options) => run_batch(&options),
     };
 
-    render(parsed_data, args).expect("Unable to render");
+    result.unwrap_or_else(|error| {
+        eprintln!("{error}");
+        ExitCode::FAILURE
+    })
+}
+
+impl InputOptions {
+    /// Reads the game data from the arguments, a file or stdin. Only prompts if stdin is a
+    /// terminal and nothing else was given.
+    fn read(&self) -> Result<String, MinesweeperError> {
+        if let Some(data) = &self.data {
+            return Ok(data.clone());
+        }
+        if let Some(path) = &self.input_file {
+            return fs::read_to_string(path)
+                .map_err(|error| MinesweeperError::InputRead(path.clone(), error));
+        }
+        if let Some(game_id) = &self.game_id {
+            return self.api.client().fetch(game_id);
+        }
+
+        let mut stdin = io::stdin();
+        let mut input = String::new();
+        if stdin.is_terminal() {
+            if self.custom_input {
+                eprintln!("Please enter the data:");
+            } else {
+                eprintln!("Please enter the GameID:");
+            }
+            stdin.read_line(&mut input)
+        } else {
+            stdin.read_to_string(&mut input)
+        }
+        .map_err(|error| MinesweeperError::InputRead("stdin".to_string(), error))?;
+
+        if self.custom_input {
+            Ok(input)
+        } else {
+            self.api.client().fetch(&input)
+        }
+    }
+
+    fn parse(&self) -> Result<ParsedData, MinesweeperError> {
+        if let Some(data) = self
+            .input_file
+            .as_deref()
+            .and_then(|path| import_file(Path::new(path)))
+        {
+            return data;
+        }
+        ParserRegistry::default().parse(&self.read()?)
+    }
+}
+
+/// Imports the replay of another client based on the extension, `None` for game data files.
+fn import_file(path: &Path) -> Option<Result<ParsedData, MinesweeperError>> {
+    let registry = ImporterRegistry::default();
+    let importer = registry.find_importer(path)?;
+
+    Some(
+        fs::read(path)
+            .map_err(|error| MinesweeperError::InputRead(path.display().to_string(), error))
+            .and_then(|data| importer.import(&data)),
+    )
 }
 
-fn fetch_data(args: &RenderOptions) -> String {
-    let mut data = String::new();
-    if args.custom_input {
-        println!("Please enter the data:");
+fn run_render(options: RenderOptions) -> Result<ExitCode, MinesweeperError> {
+    let data = options.input.parse()?;
+    let outcome = GameOutcome::of(&data);
 
-        let stdin = io::stdin();
-        stdin.read_line(&mut data).unwrap();
-        data
+    let output = options.output.as_deref();
+    let format = output_format(&data, &options.style, output)?;
+    let output = output
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("output.{}", format.extension()));
+    render(data, &options.style, &output, format, true)?;
+
+    // Keep stdout clean when the render itself is written to it
+    if output == "-" {
+        eprintln!("\nResult: {outcome}");
     } else {
-        println!("Please enter the GameID:");
+        println!("\nResult: {outcome}");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run_info(options: &InputOptions) -> Result<ExitCode, MinesweeperError> {
+    let data = options.parse()?;
+    let metadata = &data.metadata;
+    let ticks = actions_in_order(&data)
+        .last()
+        .map_or(0, |action| action.tick());
 
-        let gameid = &mut String::new();
-        let stdin = io::stdin();
-        stdin.read_line(gameid).unwrap();
+    println!("Board:    {}x{}", metadata.x_size, metadata.y_size);
+    println!("Mines:    {}", data.game_board.mine_count);
+    println!("Opens:    {}", data.open_data.len());
+    println!("Flags:    {}", data.flag_data.len());
+    println!("Chords:   {}", data.chord_data.len());
+    println!(
+        "Duration: {:.3}s ({ticks} ticks of {}ms)",
+        metadata.ticks_to_ms(ticks) as f64 / 1000.0,
+        metadata.timeunits
+    );
+    println!("Result:   {}", GameOutcome::of(&data));
+    Ok(ExitCode::SUCCESS)
+}
 
-        let request_data =
-            ureq::get(format!("https://api.greev.eu/v2/stats/minesweeper/game/{gameid}").as_ref())
-                .call()
-                .expect("Unable to fetch Data")
-                .into_string()
-                .expect("Unable to parse Data");
+fn run_stats(options: &StatsOptions) -> Result<ExitCode, MinesweeperError> {
+    let data = options.input.parse()?;
+    let statistics = Statistics::of(&data);
+    let analysis = options.clicks.then(|| solver::analyse(&data));
 
-        let v: ApiData = serde_json::from_str(request_data.as_ref()).expect("Unable to parse Data");
-        v.game_data
+    match options.format {
+        StatsFormat::Json => {
+            let json = match analysis {
+                Some(analysis) => serde_json::json!({
+                    "statistics": statistics,
+                    "clicks": analysis,
+                }),
+                None => serde_json::json!(statistics),
+            };
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&json).expect("Unable to serialize statistics")
+            );
+        }
+        StatsFormat::Text => {
+            print_statistics(&data, &statistics);
+            if let Some(analysis) = analysis {
+                println!();
+                print_analysis(&analysis);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Fails if any issue is an error.
+fn run_validate(options: &InputOptions) -> Result<ExitCode, MinesweeperError> {
+    let imported = options
+        .input_file
+        .as_deref()
+        .and_then(|path| import_file(Path::new(path)));
+    let issues = match imported {
+        Some(data) => validate_parsed(&data?),
+        None => validate(&ParserRegistry::default(), &options.read()?),
+    };
+
+    if issues.is_empty() {
+        println!("No problems found");
+    }
+    for issue in &issues {
+        println!("{issue}");
     }
+
+    Ok(
+        if issues.iter().any(|issue| issue.severity == Severity::Error) {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        },
+    )
 }
 
-fn render(data: ParsedData, options: RenderOptions) -> Result<(), MinesweeperError> {
-    let sprite = load_textures(&options, &data.metadata);
+fn run_convert(options: &ConvertOptions) -> Result<ExitCode, MinesweeperError> {
+    let data = options.input.parse()?;
+    let converted = match ExporterRegistry::default().find_exporter(&options.to) {
+        Some(exporter) => exporter.export(&data)?,
+        None => SerializerRegistry::default().serialize(&data, &options.to)?,
+    };
+    write_output(&options.output, &converted)?;
+    Ok(ExitCode::SUCCESS)
+}
 
-    let mut renderer = Renderer::new(
-        data.metadata,
-        data.game_board,
-        data.open_data,
-        data.flag_data,
-        sprite.as_slice(),
-        &options,
+/// The savings go to stderr so the minified game can be piped.
+fn run_minify(options: &RewriteOptions) -> Result<ExitCode, MinesweeperError> {
+    let minified = minify(
+        &ParserRegistry::default(),
+        &SerializerRegistry::default(),
+        &options.input.read()?,
+    )?;
+    write_output(&options.output, &minified.data)?;
+
+    eprintln!(
+        "Saved {} of {} bytes ({:.1}%)",
+        minified.saved(),
+        minified.original_len,
+        minified.saved_percent()
     );
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Lines that fail to upgrade are kept as they are and reported, so an archive never loses games.
+fn run_upgrade(options: &RewriteOptions) -> Result<ExitCode, MinesweeperError> {
+    let parsers = ParserRegistry::default();
+    let input = options.input.read()?;
+
+    let mut lines = Vec::new();
+    let mut games = 0;
+    let mut upgraded = 0;
+    let mut failures = 0;
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        games += 1;
+        match upgrade_v1(&parsers, line) {
+            Ok(game) => {
+                if game != line.trim() {
+                    upgraded += 1;
+                }
+                lines.push(game);
+            }
+            Err(error) => {
+                eprintln!("Line {}: {error}", index + 1);
+                failures += 1;
+                lines.push(line.to_string());
+            }
+        }
+    }
+    write_output(&options.output, &lines.join("\n"))?;
+
+    eprintln!("Upgraded {upgraded} of {games} games");
+    Ok(if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn run_fetch(options: &FetchOptions) -> Result<ExitCode, MinesweeperError> {
+    let data = options.api.client().fetch(&options.game_id)?;
+    write_output(&options.output, &data)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Writes `content` with a trailing newline to the file at `path`, `-` is stdout.
+fn write_output(path: &str, content: &str) -> Result<(), MinesweeperError> {
+    if path == "-" {
+        // Unlike println this reports a closed pipe instead of panicking
+        return writeln!(io::stdout().lock(), "{content}").map_err(MinesweeperError::Write);
+    }
+
+    fs::write(path, format!("{content}\n"))
+        .map_err(|error| MinesweeperError::OutputCreate(path.to_string(), error))
+}
+
+/// The explicitly chosen format, the one matching the extension of `output` or the default for
+/// the render type.
+fn output_format(
+    data: &ParsedData,
+    style: &StyleOptions,
+    output: Option<&str>,
+) -> Result<OutputFormat, MinesweeperError> {
+    match (style.format, output) {
+        (Some(format), _) => Ok(format),
+        (None, None | Some("-")) => Ok(OutputFormat::for_render_type(
+            style
+                .force_type
+                .unwrap_or_else(|| RenderType::for_metadata(&data.metadata)),
+        )),
+        (None, Some(path)) => OutputFormat::from_path(path)
+            .ok_or_else(|| MinesweeperError::UnknownOutputFormat(path.to_string())),
+    }
+}
+
+/// The forced render type, which has to fit the format, or the one of the format.
+fn render_type(style: &StyleOptions, format: OutputFormat) -> Result<RenderType, MinesweeperError> {
+    match style.force_type {
+        Some(RenderType::Gif) if !matches!(format, OutputFormat::Gif) => Err(
+            MinesweeperError::NotAnimatable(format.extension().to_string()),
+        ),
+        Some(render_type) => Ok(render_type),
+        None => Ok(format.render_type()),
+    }
+}
+
+fn render(
+    data: ParsedData,
+    style: &StyleOptions,
+    output: &str,
+    format: OutputFormat,
+    progress: bool,
+) -> Result<(), MinesweeperError> {
+    let render_type = render_type(style, format)?;
+    let sprite = load_textures(style.custom_textures.as_deref(), render_type)?;
+
+    let analysis = style.highlight_guesses.then(|| solver::analyse(&data));
+    let mut renderer = Renderer::new(data, sprite.as_slice(), style.repeat)?;
+    if let Some(analysis) = analysis {
+        renderer.highlight_clicks(&analysis);
+    }
+    if style.heatmap {
+        renderer.show_probabilities();
+    }
+    if !progress {
+        renderer.hide_progress();
+    }
+    if matches!(render_type, RenderType::Image) {
+        renderer.still_image();
+    }
+
+    renderer.render_to_path(output, format)?;
+
+    if renderer.estimated_frames() > 0 {
+        eprintln!(
+            "{output}: the mine probabilities of {} frames are estimates, their boards were too \
+             complex to calculate exactly",
+            renderer.estimated_frames()
+        );
+    }
+    Ok(())
+}
+
+/// A single game of a batch.
+enum BatchJob {
+    GameId(String),
+    Data { index: usize, data: String },
+    File(PathBuf),
+}
+
+impl BatchJob {
+    /// The name of the render without extension.
+    fn name(&self) -> String {
+        match self {
+            BatchJob::GameId(game_id) => game_id.clone(),
+            BatchJob::Data { index, .. } => index.to_string(),
+            BatchJob::File(path) => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn load(&self, api: &ApiClient) -> Result<String, MinesweeperError> {
+        match self {
+            BatchJob::GameId(game_id) => api.fetch(game_id),
+            BatchJob::Data { data, .. } => Ok(data.clone()),
+            BatchJob::File(path) => fs::read_to_string(path)
+                .map_err(|error| MinesweeperError::InputRead(path.display().to_string(), error)),
+        }
+    }
+
+    fn render(&self, options: &BatchOptions, api: &ApiClient) -> Result<PathBuf, MinesweeperError> {
+        let imported = match self {
+            BatchJob::File(path) => import_file(path),
+            _ => None,
+        };
+        let data = match imported {
+            Some(data) => data?,
+            None => ParserRegistry::default().parse(&self.load(api)?)?,
+        };
+        let format = output_format(&data, &options.style, None)?;
+        let output = options
+            .output_dir
+            .join(format!("{}.{}", self.name(), format.extension()));
+
+        render(
+            data,
+            &options.style,
+            &output.to_string_lossy(),
+            format,
+            false,
+        )?;
+        Ok(output)
+    }
+}
+
+/// Lines containing a version separator are raw game data, all others are GameIDs.
+fn batch_jobs(input: &Path) -> Result<Vec<BatchJob>, MinesweeperError> {
+    let read_error = |error| MinesweeperError::InputRead(input.display().to_string(), error);
+
+    if input.is_dir() {
+        let mut files = fs::read_dir(input)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(read_error)?;
+        files.retain(|path| path.is_file());
+        files.sort();
+        return Ok(files.into_iter().map(BatchJob::File).collect());
+    }
 
-    if let Some(t) = &options.force_type {
-        match t {
-            RenderType::Image => renderer.render_jpeg(),
-            RenderType::Gif => renderer.render_gif(),
+    let content = fs::read_to_string(input).map_err(read_error)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(index, line)| {
+            if line.contains('=') {
+                BatchJob::Data {
+                    index: index + 1,
+                    data: line.to_string(),
+                }
+            } else {
+                BatchJob::GameId(line.to_string())
+            }
+        })
+        .collect())
+}
+
+/// Renders every game of the batch, fails if any of them failed.
+fn run_batch(options: &BatchOptions) -> Result<ExitCode, MinesweeperError> {
+    let jobs = batch_jobs(&options.input)?;
+    fs::create_dir_all(&options.output_dir).map_err(|error| {
+        MinesweeperError::OutputCreate(options.output_dir.display().to_string(), error)
+    })?;
+
+    let workers = options
+        .jobs
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1)
+        .clamp(1, jobs.len().max(1));
+    let api = options.api.client();
+    let next = AtomicUsize::new(0);
+    let failures = AtomicUsize::new(0);
+
+    thread::scope(|scope| -> Result<(), MinesweeperError> {
+        for _ in 0..workers {
+            let worker = || {
+                while let Some(job) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    // A panic must only fail its own game, not the whole batch
+                    let error =
+                        match panic::catch_unwind(AssertUnwindSafe(|| job.render(options, &api))) {
+                            Ok(Ok(output)) => {
+                                println!("{}: {}", job.name(), output.display());
+                                continue;
+                            }
+                            Ok(Err(error)) => error.to_string(),
+                            Err(payload) => panic_message(payload.as_ref()),
+                        };
+
+                    failures.fetch_add(1, Ordering::Relaxed);
+                    eprintln!("{}: {error}", job.name());
+                }
+            };
+            // Spawned threads get a smaller stack than the main thread by default
+            thread::Builder::new()
+                .stack_size(WORKER_STACK_SIZE)
+                .spawn_scoped(scope, worker)
+                .map_err(MinesweeperError::WorkerSpawn)?;
         }
-    } else if renderer.metadata.y_size >= 32 || renderer.metadata.x_size >= 32 {
-        renderer.render_jpeg()
+        Ok(())
+    })?;
+
+    let failures = failures.into_inner();
+    println!(
+        "\nRendered {} of {} games, {failures} failed",
+        jobs.len() - failures,
+        jobs.len()
+    );
+    Ok(if failures == 0 {
+        ExitCode::SUCCESS
     } else {
-        renderer.render_gif()
+        ExitCode::FAILURE
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("unknown error");
+    format!("rendering panicked: {message}")
+}
+
+fn print_statistics(data: &ParsedData, statistics: &Statistics) {
+    println!("Result:      {}", GameOutcome::of(data));
+    println!("Time:        {:.3}s", statistics.time_ms as f64 / 1000.0);
+    println!(
+        "3BV:         {}/{}",
+        statistics.solved_bbbv, statistics.bbbv
+    );
+    println!("3BV/s:       {:.3}", statistics.bbbv_per_second);
+    println!("Openings:    {}", statistics.openings);
+    println!("Islands:     {}", statistics.islands);
+    println!(
+        "Clicks:      {} ({} left, {} right, {} double)",
+        statistics.clicks,
+        statistics.left_clicks,
+        statistics.right_clicks,
+        statistics.double_clicks
+    );
+    println!("Efficiency:  {:.0}%", statistics.efficiency);
+    println!("IOE:         {:.3}", statistics.ioe);
+    println!("RQP:         {:.3}", statistics.rqp);
+    println!("Correctness: {:.3}", statistics.correctness);
+}
+
+fn print_analysis(analysis: &[ClickAnalysis]) {
+    for click in analysis {
+        println!(
+            "Tick {:>6}: open {}|{} {}",
+            click.tick, click.coord.x, click.coord.y, click.kind
+        );
     }
+
+    let count = |kind: ClickKind| analysis.iter().filter(|c| c.kind == kind).count();
+    println!(
+        "\n{} forced, {} guesses, {} blunders",
+        count(ClickKind::Forced),
+        count(ClickKind::Guess),
+        count(ClickKind::Blunder)
+    );
 }
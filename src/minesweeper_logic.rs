@@ -1,23 +1,33 @@
-use crate::parsers::parser::Metadata;
+use std::fmt;
 
-#[derive(Debug)]
+use serde::Serialize;
+
+use crate::grid::{Coord, Grid};
+use crate::parsers::parser::{Metadata, ParsedData};
+use crate::replay::played_actions;
+
+/// The state of a game board, mutated by replaying the open and flag actions on it.
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct Board {
-    pub fields: Vec<Vec<Field>>,
-    pub changed_fields: Vec<Vec<bool>>,
+    pub fields: Grid<Field>,
+    pub changed_fields: Grid<bool>,
     pub metadata: Metadata,
     pub open_fields: u32,
     pub mine_count: u32,
     pub total_fields: u32,
+    /// The first mine that got opened.
+    pub exploded: Option<Coord>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Field {
+    /// Number of mines surrounding this field.
     pub value: u8,
     pub field_state: FieldState,
     pub mine: bool,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
 pub enum FieldState {
     Open,
     Closed,
@@ -26,48 +36,162 @@ pub enum FieldState {
 }
 
 impl Board {
-    pub(crate) fn open_field(&mut self, x: usize, y: usize) {
-        let field = &mut self.fields[y][x];
+    /// Creates a closed board with the given mines, every mine has to be on the board. A mine
+    /// listed more than once is only placed once.
+    pub fn new(metadata: &Metadata, mines: &[Coord]) -> Self {
+        let width = metadata.x_size as usize;
+        let height = metadata.y_size as usize;
 
-        //If flagged or already open return
-        if field.field_state != FieldState::Closed {
-            return;
+        let mut board = Board {
+            fields: Grid::new(width, height, Field::new()),
+            changed_fields: Grid::new(width, height, true),
+            metadata: metadata.clone(),
+            mine_count: 0,
+            open_fields: 0,
+            total_fields: (width * height) as u32,
+            exploded: None,
+        };
+
+        for &mine in mines {
+            if !board.fields[mine].mine {
+                board.fields[mine].mine = true;
+                board.mine_count += 1;
+            }
+        }
+
+        for coord in board.fields.coords() {
+            if board.fields[coord].mine {
+                continue;
+            }
+
+            board.fields[coord].value = board
+                .fields
+                .neighbours(coord)
+                .filter(|&neighbour| board.fields[neighbour].mine)
+                .count() as u8;
         }
 
-        field.field_state = FieldState::Open;
-        self.changed_fields[y][x] = true;
-        self.open_fields += 1;
+        board
+    }
+
+    /// Opens the field and, if it has no surrounding mines, all of its neighbours.
+    pub fn open_field(&mut self, coord: Coord) {
+        let mut stack = vec![coord];
+
+        while let Some(coord) = stack.pop() {
+            let field = &mut self.fields[coord];
+
+            //If flagged or already open skip it
+            if field.field_state != FieldState::Closed {
+                continue;
+            }
 
-        if field.mine {
+            field.field_state = FieldState::Open;
+            self.changed_fields[coord] = true;
+            self.open_fields += 1;
+
+            if field.mine {
+                self.exploded.get_or_insert(coord);
+                continue;
+            }
+
+            if field.value == 0 {
+                stack.extend(self.fields.neighbours(coord));
+            }
+        }
+    }
+
+    /// Chords an open number: if as many neighbours are flagged as the number says, all other
+    /// closed neighbours get opened. A wrong flag therefore opens a mine.
+    pub fn chord_field(&mut self, coord: Coord) {
+        let field = &self.fields[coord];
+        if field.field_state != FieldState::Open || field.mine {
             return;
         }
 
-        if field.value == 0 {
-            for xd in -1..=1_i32 {
-                for yd in -1..=1_i32 {
-                    let xx = xd + x as i32;
-                    let yy = yd + y as i32;
-                    if xx < 0
-                        || xx >= self.metadata.x_size
-                        || yy < 0
-                        || yy >= self.metadata.y_size
-                        || xd == 0 && yd == 0
-                    {
-                        continue;
-                    }
-                    self.open_field(xx as usize, yy as usize)
-                }
+        let neighbours: Vec<Coord> = self.fields.neighbours(coord).collect();
+        let flags = neighbours
+            .iter()
+            .filter(|&&neighbour| self.fields[neighbour].field_state == FieldState::Flagged)
+            .count();
+        if flags != field.value as usize {
+            return;
+        }
+
+        for neighbour in neighbours {
+            self.open_field(neighbour);
+        }
+    }
+
+    /// Whether every field without a mine is open and no mine went off.
+    pub fn is_solved(&self) -> bool {
+        self.exploded.is_none() && self.open_fields == self.safe_fields()
+    }
+
+    pub fn calculate_done_percentage(&self) -> u32 {
+        let safe_fields = self.safe_fields();
+        if safe_fields == 0 {
+            return 100;
+        }
+        ((self.open_fields as f32 / safe_fields as f32) * 100_f32) as u32
+    }
+
+    fn safe_fields(&self) -> u32 {
+        self.total_fields.saturating_sub(self.mine_count)
+    }
+}
+
+/// How a game ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOutcome {
+    Won,
+    /// A mine was opened at `coord` on tick `tick`.
+    Lost {
+        coord: Coord,
+        tick: i64,
+    },
+    /// The game ended before the board was solved.
+    Abandoned,
+}
+
+impl GameOutcome {
+    /// Replays the actions of the game to find out how it ended, see [`played_actions`].
+    pub fn of(data: &ParsedData) -> GameOutcome {
+        let mut board = data.game_board.clone();
+
+        for action in played_actions(data) {
+            action.apply(&mut board);
+
+            if let Some(coord) = board.exploded {
+                return GameOutcome::Lost {
+                    coord,
+                    tick: action.tick(),
+                };
             }
         }
+
+        if board.is_solved() {
+            GameOutcome::Won
+        } else {
+            GameOutcome::Abandoned
+        }
     }
+}
 
-    pub(crate) fn calculate_done_percentage(&self) -> u32 {
-        ((self.open_fields as f32 / (self.total_fields - self.mine_count) as f32) * 100_f32) as u32
+impl fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameOutcome::Won => write!(f, "Won"),
+            GameOutcome::Lost { coord, tick } => {
+                write!(f, "Lost at {}|{} on tick {tick}", coord.x, coord.y)
+            }
+            GameOutcome::Abandoned => write!(f, "Abandoned"),
+        }
     }
 }
 
 impl Field {
-    pub(crate) fn new() -> Self {
+    pub fn new() -> Self {
         Field {
             value: 0,
             field_state: FieldState::Closed,
@@ -75,3 +199,81 @@ impl Field {
         }
     }
 }
+
+impl Default for Field {
+    fn default() -> Self {
+        Field::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(x_size: i32, y_size: i32) -> Metadata {
+        Metadata {
+            x_size,
+            y_size,
+            timeunits: 50,
+        }
+    }
+
+    #[test]
+    fn duplicate_mines_are_placed_once() {
+        let mine = Coord::new(0, 0);
+        let mut board = Board::new(&metadata(3, 3), &[mine, mine]);
+        assert_eq!(board.mine_count, 1);
+
+        board.open_field(Coord::new(2, 2));
+        assert!(board.is_solved());
+        assert_eq!(board.calculate_done_percentage(), 100);
+    }
+
+    #[test]
+    fn opening_large_empty_board_does_not_recurse() {
+        let mut board = Board::new(&metadata(1000, 1000), &[]);
+        board.open_field(Coord::new(0, 0));
+
+        assert_eq!(board.open_fields, 1_000_000);
+        assert!(board.is_solved());
+    }
+
+    /// Flags `flag` on a 3x2 board with a mine in the top left corner and chords the 1 below it.
+    fn chord_with_flag(flag: Coord) -> Board {
+        let mut board = Board::new(&metadata(3, 2), &[Coord::new(0, 0)]);
+        let number = Coord::new(1, 1);
+        board.open_field(number);
+        board.fields[flag].field_state = FieldState::Flagged;
+
+        board.chord_field(number);
+        board
+    }
+
+    #[test]
+    fn chord_with_correct_flag_opens_neighbours() {
+        let board = chord_with_flag(Coord::new(0, 0));
+
+        assert_eq!(board.exploded, None);
+        assert!(board.is_solved());
+    }
+
+    #[test]
+    fn chord_with_wrong_flag_opens_mine() {
+        let board = chord_with_flag(Coord::new(0, 1));
+
+        assert_eq!(board.exploded, Some(Coord::new(0, 0)));
+        assert_eq!(
+            board.fields[Coord::new(0, 1)].field_state,
+            FieldState::Flagged
+        );
+    }
+
+    #[test]
+    fn board_full_of_mines_is_solved() {
+        let mine = Coord::new(0, 0);
+        let board = Board::new(&metadata(1, 1), &[mine, mine]);
+
+        assert!(board.is_solved());
+        assert_eq!(board.calculate_done_percentage(), 100);
+    }
+}
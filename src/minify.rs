@@ -0,0 +1,91 @@
+use serde::Serialize;
+
+use crate::error::MinesweeperError;
+use crate::parsers::registry::{split_version, ParserRegistry, SerializerRegistry};
+
+/// A game re-encoded by [`minify`].
+#[derive(Clone, Debug, Serialize)]
+pub struct Minified {
+    pub data: String,
+    /// Length of the original game in bytes, without surrounding whitespace.
+    pub original_len: usize,
+    pub minified_len: usize,
+}
+
+impl Minified {
+    pub fn saved(&self) -> usize {
+        self.original_len - self.minified_len
+    }
+
+    /// Saved bytes in percent of the original length.
+    pub fn saved_percent(&self) -> f64 {
+        if self.original_len == 0 {
+            return 0.0;
+        }
+        self.saved() as f64 / self.original_len as f64 * 100.0
+    }
+}
+
+/// Re-encodes a game in its own version with the shortest entries.
+///
+/// The serializers write every entry in the compact form whenever its coordinates fit. The result
+/// is parsed again and has to match the original game exactly, if it is not shorter the original
+/// is kept.
+pub fn minify(
+    parsers: &ParserRegistry,
+    serializers: &SerializerRegistry,
+    data: &str,
+) -> Result<Minified, MinesweeperError> {
+    let data = data.trim();
+    let (version, _) = split_version(data)?;
+    let parsed = parsers.parse(data)?;
+
+    let mut minified = serializers.serialize(&parsed, version)?;
+    if parsers.parse(&minified)? != parsed {
+        return Err(MinesweeperError::Mismatch {
+            from: version.to_string(),
+            to: version.to_string(),
+        });
+    }
+    if minified.len() >= data.len() {
+        minified = data.to_string();
+    }
+
+    Ok(Minified {
+        original_len: data.len(),
+        minified_len: minified.len(),
+        data: minified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minify_default(data: &str) -> Minified {
+        minify(
+            &ParserRegistry::default(),
+            &SerializerRegistry::default(),
+            data,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn shortens_long_entries() {
+        let minified = minify_default("2=4x2,50+0|0;1|0+1|1:A;2|1:B+0|0:CP");
+
+        assert_eq!(minified.data, "2=4x2,50+0010+11A;21B+00CP");
+        assert_eq!(minified.saved(), 9);
+    }
+
+    #[test]
+    fn keeps_minified_game() {
+        let game = "2=4x2,50+0010+11A;21B+00CP";
+        let minified = minify_default(game);
+
+        assert_eq!(minified.data, game);
+        assert_eq!(minified.saved(), 0);
+        assert_eq!(minify_default(&minified.data).data, minified.data);
+    }
+}
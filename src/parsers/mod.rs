@@ -1,3 +1,6 @@
 pub mod parser;
+pub mod registry;
+pub mod serializer;
 pub mod v1;
 pub mod v2;
+pub mod v3;
@@ -1,49 +1,81 @@
+use crate::error::{ParseError, Section};
+use crate::grid::Coord;
 use crate::minesweeper_logic::{Board, FieldState};
 use serde::{Deserialize, Serialize};
 
+/// A parser for one or more versions of the game data format.
 pub trait Iparser {
     fn supported_versions(&self) -> Vec<&str>;
-    fn parse_mine_data(&self, data: &str, metadata: &Metadata) -> Board;
-    fn parse_mine_locations(&self, data: &str) -> Vec<(i32, i32)>;
-    fn parse_flag_data(&self, data: &str) -> Vec<FlagAction>;
-    fn parse_open_data(&self, data: &str) -> Vec<OpenAction>;
-    fn parse_meta_data(&self, data: &str) -> Metadata;
+    fn parse_mine_data(&self, data: &str, metadata: &Metadata) -> Result<Board, ParseError>;
+    fn parse_mine_locations(&self, data: &str) -> Result<Vec<(i32, i32)>, ParseError>;
+    fn parse_flag_data(&self, data: &str) -> Result<Vec<FlagAction>, ParseError>;
+    fn parse_open_data(&self, data: &str) -> Result<Vec<OpenAction>, ParseError>;
+    fn parse_meta_data(&self, data: &str) -> Result<Metadata, ParseError>;
+
+    /// Chords were added in version 3, older versions cannot contain a chord section.
+    fn parse_chord_data(&self, data: &str) -> Result<Vec<OpenAction>, ParseError> {
+        if data.is_empty() {
+            return Ok(Vec::new());
+        }
+        Err(ParseError::new(
+            Section::Chords,
+            0,
+            data,
+            "chords are not supported by this version",
+        ))
+    }
 }
 
+/// Largest board a game may have, bigger boards are rejected before they get allocated.
+pub const MAX_FIELDS: i64 = 1_000_000;
+
 #[derive(Serialize, Deserialize)]
 pub struct ApiData {
     #[serde(rename = "gameData")]
     pub game_data: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Metadata {
     pub x_size: i32,
     pub y_size: i32,
+    /// Length of a single tick in milliseconds.
     pub timeunits: i32,
 }
 
-#[derive(Debug)]
+impl Metadata {
+    /// Converts ticks to milliseconds, saturating instead of overflowing on absurd times.
+    pub fn ticks_to_ms(&self, ticks: i64) -> i64 {
+        ticks.saturating_mul(self.timeunits as i64)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct FlagAction {
     pub x: i32,
     pub y: i32,
+    /// Ticks since the previous flag action.
     pub time: i64,
     pub action: Action,
+    /// Ticks since the start of the game.
     pub total_time: i64,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Action {
     Place,
     Remove,
     Toggle,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct OpenAction {
     pub x: i32,
     pub y: i32,
+    /// Ticks since the previous open action.
     pub time: i64,
+    /// Ticks since the start of the game.
     pub total_time: i64,
 }
 
@@ -53,29 +85,48 @@ pub enum ActionType {
     Flag,
 }
 
+/// A fully parsed game.
+///
+/// Only serializable, games are read back through [`crate::json::JsonGame`] which checks them
+/// before building the board.
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct ParsedData {
     pub metadata: Metadata,
     pub game_board: Board,
     pub open_data: Vec<OpenAction>,
     pub flag_data: Vec<FlagAction>,
+    /// Middle clicks on open numbers, see [`Board::chord_field`].
+    pub chord_data: Vec<OpenAction>,
+}
+
+/// A game whose sections got parsed without building the board, so the mines are unchecked.
+pub struct RawGame {
+    pub metadata: Metadata,
+    pub mines: Vec<(i32, i32)>,
+    pub open_data: Vec<OpenAction>,
+    pub flag_data: Vec<FlagAction>,
+    pub chord_data: Vec<OpenAction>,
 }
 
 impl FlagAction {
-    pub(crate) fn perform_action(&self, board: &mut Board) {
-        match self.action {
-            Action::Place => {
-                board.fields[self.y as usize][self.x as usize].field_state = FieldState::Flagged;
-                board.changed_fields[self.y as usize][self.x as usize] = true;
-            }
-            Action::Remove => {
-                board.fields[self.y as usize][self.x as usize].field_state = FieldState::Closed;
-                board.changed_fields[self.y as usize][self.x as usize] = true;
-            }
-            Action::Toggle => {
-                board.fields[self.y as usize][self.x as usize].field_state =
-                    FieldState::UnsureFlagged;
-                board.changed_fields[self.y as usize][self.x as usize] = true;
-            }
-        }
+    pub fn coord(&self) -> Coord {
+        Coord::new(self.x as usize, self.y as usize)
+    }
+
+    pub fn perform_action(&self, board: &mut Board) {
+        let coord = self.coord();
+
+        board.fields[coord].field_state = match self.action {
+            Action::Place => FieldState::Flagged,
+            Action::Remove => FieldState::Closed,
+            Action::Toggle => FieldState::UnsureFlagged,
+        };
+        board.changed_fields[coord] = true;
+    }
+}
+
+impl OpenAction {
+    pub fn coord(&self) -> Coord {
+        Coord::new(self.x as usize, self.y as usize)
     }
 }
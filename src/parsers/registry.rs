@@ -0,0 +1,244 @@
+use crate::error::{MinesweeperError, ParseError, Section};
+use crate::parsers::parser::{Iparser, OpenAction, ParsedData, RawGame};
+use crate::parsers::serializer::Iserializer;
+use crate::parsers::v1::parser::ParserV1;
+use crate::parsers::v1::serializer::SerializerV1;
+use crate::parsers::v2::parser::ParserV2;
+use crate::parsers::v2::serializer::SerializerV2;
+use crate::parsers::v3::parser::ParserV3;
+use crate::parsers::v3::serializer::SerializerV3;
+
+/// Owns the available parsers and runs the whole parse pipeline for a game string.
+///
+/// Parsers registered later take precedence over earlier ones, so a custom parser can replace a
+/// built-in one for the same version.
+pub struct ParserRegistry {
+    parsers: Vec<Box<dyn Iparser>>,
+}
+
+impl Default for ParserRegistry {
+    /// Creates a registry containing all built-in parsers.
+    fn default() -> Self {
+        let mut registry = ParserRegistry::new();
+        registry.register(Box::new(ParserV1));
+        registry.register(Box::new(ParserV2));
+        registry.register(Box::new(ParserV3));
+        registry
+    }
+}
+
+impl ParserRegistry {
+    /// Creates a registry without any parsers.
+    pub fn new() -> Self {
+        ParserRegistry {
+            parsers: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, parser: Box<dyn Iparser>) {
+        self.parsers.push(parser);
+    }
+
+    /// All versions that can be parsed, without duplicates.
+    pub fn supported_versions(&self) -> Vec<&str> {
+        let mut versions: Vec<&str> = Vec::new();
+
+        for version in self.parsers.iter().flat_map(|p| p.supported_versions()) {
+            if !versions.contains(&version) {
+                versions.push(version);
+            }
+        }
+
+        versions
+    }
+
+    pub fn find_parser(&self, version: &str) -> Option<&dyn Iparser> {
+        self.parsers
+            .iter()
+            .rev()
+            .find(|p| p.supported_versions().contains(&version))
+            .map(|p| p.as_ref())
+    }
+
+    pub fn parse(&self, data: &str) -> Result<ParsedData, MinesweeperError> {
+        let (parser, sections) = self.split_sections(data)?;
+        let section = |id: usize, section: Section| {
+            sections
+                .get(id)
+                .copied()
+                .ok_or(MinesweeperError::MissingSection(section))
+        };
+
+        let metadata = parser.parse_meta_data(section(0, Section::Metadata)?)?;
+
+        let parsed_data = ParsedData {
+            game_board: parser.parse_mine_data(section(1, Section::Mines)?, &metadata)?,
+            open_data: parser.parse_open_data(section(2, Section::Opens)?)?,
+            flag_data: parser.parse_flag_data(section(3, Section::Flags)?)?,
+            chord_data: parse_chord_data(parser, &sections)?,
+            metadata,
+        };
+
+        // Replaying actions outside of the board would panic
+        let actions = parsed_data
+            .open_data
+            .iter()
+            .map(|open| (Section::Opens, open.x, open.y))
+            .enumerate()
+            .chain(
+                parsed_data
+                    .flag_data
+                    .iter()
+                    .map(|flag| (Section::Flags, flag.x, flag.y))
+                    .enumerate(),
+            )
+            .chain(
+                parsed_data
+                    .chord_data
+                    .iter()
+                    .map(|chord| (Section::Chords, chord.x, chord.y))
+                    .enumerate(),
+            );
+        for (index, (section, x, y)) in actions {
+            if parsed_data
+                .game_board
+                .fields
+                .coord(x as i64, y as i64)
+                .is_none()
+            {
+                return Err(ParseError::new(
+                    section,
+                    index,
+                    &format!("{x}|{y}"),
+                    "action is outside of the board",
+                )
+                .into());
+            }
+        }
+
+        Ok(parsed_data)
+    }
+
+    /// Parses the sections of a game without building the board, so games with invalid mines
+    /// can still be inspected.
+    pub fn parse_raw(&self, data: &str) -> Result<RawGame, MinesweeperError> {
+        let (parser, sections) = self.split_sections(data)?;
+        let section = |id: usize, section: Section| {
+            sections
+                .get(id)
+                .copied()
+                .ok_or(MinesweeperError::MissingSection(section))
+        };
+
+        Ok(RawGame {
+            metadata: parser.parse_meta_data(section(0, Section::Metadata)?)?,
+            mines: parser.parse_mine_locations(section(1, Section::Mines)?)?,
+            open_data: parser.parse_open_data(section(2, Section::Opens)?)?,
+            flag_data: parser.parse_flag_data(section(3, Section::Flags)?)?,
+            chord_data: parse_chord_data(parser, &sections)?,
+        })
+    }
+
+    /// Finds the parser for the version of `data` and splits the rest into its sections.
+    fn split_sections<'a>(
+        &self,
+        data: &'a str,
+    ) -> Result<(&dyn Iparser, Vec<&'a str>), MinesweeperError> {
+        let (version, body) = split_version(data)?;
+        let parser =
+            self.find_parser(version)
+                .ok_or_else(|| MinesweeperError::UnsupportedVersion {
+                    version: version.to_string(),
+                    supported: self
+                        .supported_versions()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect(),
+                })?;
+
+        Ok((parser, body.split('+').map(str::trim).collect()))
+    }
+}
+
+/// Owns the available serializers, the counterpart of [`ParserRegistry`].
+pub struct SerializerRegistry {
+    serializers: Vec<Box<dyn Iserializer>>,
+}
+
+impl Default for SerializerRegistry {
+    /// Creates a registry containing all built-in serializers.
+    fn default() -> Self {
+        let mut registry = SerializerRegistry::new();
+        registry.register(Box::new(SerializerV1));
+        registry.register(Box::new(SerializerV2));
+        registry.register(Box::new(SerializerV3));
+        registry
+    }
+}
+
+impl SerializerRegistry {
+    /// Creates a registry without any serializers.
+    pub fn new() -> Self {
+        SerializerRegistry {
+            serializers: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, serializer: Box<dyn Iserializer>) {
+        self.serializers.push(serializer);
+    }
+
+    /// All versions that can be written, without duplicates.
+    pub fn supported_versions(&self) -> Vec<&str> {
+        let mut versions: Vec<&str> = Vec::new();
+
+        for version in self.serializers.iter().map(|s| s.version()) {
+            if !versions.contains(&version) {
+                versions.push(version);
+            }
+        }
+
+        versions
+    }
+
+    pub fn find_serializer(&self, version: &str) -> Option<&dyn Iserializer> {
+        self.serializers
+            .iter()
+            .rev()
+            .find(|s| s.version() == version)
+            .map(|s| s.as_ref())
+    }
+
+    pub fn serialize(&self, data: &ParsedData, version: &str) -> Result<String, MinesweeperError> {
+        let serializer =
+            self.find_serializer(version)
+                .ok_or_else(|| MinesweeperError::UnsupportedVersion {
+                    version: version.to_string(),
+                    supported: self
+                        .supported_versions()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect(),
+                })?;
+
+        serializer.serialize(data)
+    }
+}
+
+/// The chord section is optional, games without chords may leave it out.
+fn parse_chord_data(
+    parser: &dyn Iparser,
+    sections: &[&str],
+) -> Result<Vec<OpenAction>, MinesweeperError> {
+    match sections.get(4) {
+        Some(data) => Ok(parser.parse_chord_data(data)?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Splits a game string into its version prefix (e.g. `2` for `2=...`) and the remaining data.
+pub(crate) fn split_version(data: &str) -> Result<(&str, &str), MinesweeperError> {
+    data.trim()
+        .split_once('=')
+        .ok_or(MinesweeperError::MissingVersion)
+}
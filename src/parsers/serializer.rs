@@ -0,0 +1,8 @@
+use crate::error::MinesweeperError;
+use crate::parsers::parser::ParsedData;
+
+/// Writes a [`ParsedData`] back into a game data string.
+pub trait Iserializer {
+    fn version(&self) -> &str;
+    fn serialize(&self, data: &ParsedData) -> Result<String, MinesweeperError>;
+}
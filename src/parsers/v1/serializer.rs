@@ -0,0 +1,99 @@
+use crate::error::{MinesweeperError, Section};
+use crate::parsers::parser::{Action, ParsedData};
+use crate::parsers::serializer::Iserializer;
+use crate::parsers::v2::serializer::{
+    encode_position, mine_locations, serialize_mine_locations, EncodedPosition,
+};
+
+/// Version 1 always measures time in ticks of 50ms.
+const TIMEUNITS: i32 = 50;
+
+pub struct SerializerV1;
+
+impl Iserializer for SerializerV1 {
+    fn version(&self) -> &str {
+        "1"
+    }
+
+    fn serialize(&self, data: &ParsedData) -> Result<String, MinesweeperError> {
+        let metadata = &data.metadata;
+        if metadata.timeunits != TIMEUNITS {
+            return Err(MinesweeperError::Serialize {
+                section: Section::Metadata,
+                index: 0,
+                reason: format!(
+                    "version 1 needs timeunits of {TIMEUNITS}ms, not {}",
+                    metadata.timeunits
+                ),
+            });
+        }
+        if !data.chord_data.is_empty() {
+            return Err(MinesweeperError::Serialize {
+                section: Section::Chords,
+                index: 0,
+                reason: "chords need version 3".to_string(),
+            });
+        }
+
+        let mut opens = Vec::new();
+        for (index, action) in data.open_data.iter().enumerate() {
+            opens.push(serialize_position(
+                action.x,
+                action.y,
+                action.time,
+                Section::Opens,
+                index,
+            )?);
+        }
+
+        let mut flags = Vec::new();
+        for (index, action) in data.flag_data.iter().enumerate() {
+            let mut entry =
+                serialize_position(action.x, action.y, action.time, Section::Flags, index)?;
+            entry.push(match action.action {
+                Action::Place => 'P',
+                Action::Remove => 'R',
+                Action::Toggle => {
+                    return Err(MinesweeperError::Serialize {
+                        section: Section::Flags,
+                        index,
+                        reason: "toggles need version 2".to_string(),
+                    })
+                }
+            });
+            flags.push(entry);
+        }
+
+        Ok(format!(
+            "{}={}x{}+{}+{}+{}",
+            self.version(),
+            metadata.x_size,
+            metadata.y_size,
+            serialize_mine_locations(&mine_locations(data))?,
+            opens.join(";"),
+            flags.join(";"),
+        ))
+    }
+}
+
+/// Like the entries of version 2, but with the time in decimal.
+fn serialize_position(
+    x: i32,
+    y: i32,
+    time: i64,
+    section: Section,
+    index: usize,
+) -> Result<String, MinesweeperError> {
+    if time < 0 {
+        return Err(MinesweeperError::Serialize {
+            section,
+            index,
+            reason: format!("negative time {time}"),
+        });
+    }
+
+    Ok(match encode_position(x, y, section, index)? {
+        EncodedPosition::Compact(position) => format!("{position}{time}"),
+        EncodedPosition::Long(x, y) => format!("{x}|{y}:{time}"),
+    })
+}
@@ -1,8 +1,10 @@
 use std::str::FromStr;
 
-use crate::base62::decode;
-use crate::minesweeper_logic::{Board, Field};
-use crate::parsers::parser::{Action, FlagAction, Iparser, Metadata, OpenAction};
+use crate::base62::{decode, decode_fixed};
+use crate::error::{ParseError, Section};
+use crate::grid::Coord;
+use crate::minesweeper_logic::Board;
+use crate::parsers::parser::{Action, FlagAction, Iparser, Metadata, OpenAction, MAX_FIELDS};
 
 pub struct ParserV2;
 
@@ -11,246 +13,294 @@ impl Iparser for ParserV2 {
         vec!["2"]
     }
 
-    fn parse_mine_data(&self, data: &str, metadata: &Metadata) -> Board {
-        let mines = self.parse_mine_locations(data);
-
-        let mut board = Board {
-            fields: vec![vec![Field::new(); metadata.y_size as usize]; metadata.x_size as usize],
-            changed_fields: vec![vec![true; metadata.y_size as usize]; metadata.x_size as usize],
-            metadata: metadata.clone(),
-            mine_count: mines.len() as u32,
-            open_fields: 0,
-            total_fields: (metadata.y_size * metadata.x_size) as u32,
-        };
-
-        for cords in mines {
-            let x = cords.0;
-            let y = cords.1;
-            let field = &mut board.fields[y as usize][x as usize];
-            field.mine = true;
-        }
-
-        for x in 0..metadata.x_size {
-            for y in 0..metadata.y_size {
-                let field = &mut board.fields[x as usize][y as usize];
-
-                if !field.mine {
-                    continue;
-                }
-
-                for xd in -1..=1_i32 {
-                    for zd in -1..=1_i32 {
-                        let xx = x + xd;
-                        let yy = y + zd;
-                        if xx < 0
-                            || xx >= metadata.x_size
-                            || yy < 0
-                            || yy >= metadata.y_size
-                            || (zd == 0 && xd == 0)
-                        {
-                            continue;
-                        }
-
-                        let checked_field = &mut board.fields[xx as usize][yy as usize];
-                        if checked_field.mine {
-                            continue;
-                        }
-
-                        checked_field.value += 1;
-                    }
-                }
+    fn parse_mine_data(&self, data: &str, metadata: &Metadata) -> Result<Board, ParseError> {
+        let mines = self.parse_mine_locations(data)?;
+        let mut coords = Vec::with_capacity(mines.len());
+
+        for (index, (x, y)) in mines.into_iter().enumerate() {
+            if x >= metadata.x_size || y >= metadata.y_size {
+                return Err(ParseError::new(
+                    Section::Mines,
+                    index,
+                    &format!("{x}|{y}"),
+                    "mine is outside of the board",
+                ));
             }
+            coords.push(Coord::new(x as usize, y as usize));
         }
 
-        board
+        Ok(Board::new(metadata, &coords))
     }
 
-    fn parse_mine_locations(&self, data: &str) -> Vec<(i32, i32)> {
+    fn parse_mine_locations(&self, data: &str) -> Result<Vec<(i32, i32)>, ParseError> {
         let mut return_data = Vec::new();
 
         if data.chars().count() == 0 {
-            return return_data;
+            return Ok(return_data);
         }
 
-        let raw_open_fields_data: Vec<&str> = data.split(';').collect();
+        let raw_mine_data: Vec<&str> = data.split(';').collect();
 
-        for raw_open_field in raw_open_fields_data {
-            if raw_open_field.contains('|') {
-                let part = raw_open_field
-                    .split_once('|')
-                    .expect("Unable to parse mine locations");
+        for (index, raw_mine) in raw_mine_data.into_iter().enumerate() {
+            let error =
+                |entry: &str, reason: &str| ParseError::new(Section::Mines, index, entry, reason);
 
-                return_data.push((decode(part.0) as i32, decode(part.1) as i32));
+            if raw_mine.contains('|') {
+                let part = raw_mine
+                    .split_once('|')
+                    .ok_or_else(|| error(raw_mine, "missing '|'"))?;
+
+                return_data.push((
+                    decode_coordinate(part.0).map_err(|reason| {
+                        error(raw_mine, &format!("invalid x coordinate: {reason}"))
+                    })?,
+                    decode_coordinate(part.1).map_err(|reason| {
+                        error(raw_mine, &format!("invalid y coordinate: {reason}"))
+                    })?,
+                ));
             } else {
-                raw_open_field
-                    .chars()
-                    .collect::<Vec<char>>()
-                    .chunks(2)
-                    .map(|chunk| chunk.iter().collect::<String>())
-                    .for_each(|x| {
-                        let mut chars = x.chars();
-                        return_data.push((
-                            decode(
-                                chars
-                                    .next()
-                                    .expect("Unable to parse mine locations")
-                                    .to_string()
-                                    .as_str(),
-                            ) as i32,
-                            decode(
-                                chars
-                                    .next()
-                                    .expect("Unable to parse mine locations")
-                                    .to_string()
-                                    .as_str(),
-                            ) as i32,
-                        ))
-                    });
+                for chunk in raw_mine.chars().collect::<Vec<char>>().chunks(2) {
+                    let chunk_data = chunk.iter().collect::<String>();
+
+                    let (x, rest) = split_coordinate(&chunk_data).map_err(|reason| {
+                        error(&chunk_data, &format!("invalid x coordinate: {reason}"))
+                    })?;
+                    let (y, _) = split_coordinate(rest).map_err(|reason| {
+                        error(&chunk_data, &format!("invalid y coordinate: {reason}"))
+                    })?;
+
+                    return_data.push((x, y));
+                }
             }
         }
 
-        return_data
+        Ok(return_data)
     }
 
-    fn parse_flag_data(&self, data: &str) -> Vec<FlagAction> {
+    fn parse_flag_data(&self, data: &str) -> Result<Vec<FlagAction>, ParseError> {
         let mut return_data = Vec::new();
+        let mut total_time: i64 = 0;
 
         if data.chars().count() == 0 {
-            return return_data;
+            return Ok(return_data);
         }
 
-        let raw_open_fields_data: Vec<&str> = data.split(';').collect();
+        let raw_flag_data: Vec<&str> = data.split(';').collect();
 
-        for raw_open_field in raw_open_fields_data {
-            if raw_open_field.contains('|') {
-                let mut chars = raw_open_field.chars();
+        for (index, raw_flag) in raw_flag_data.into_iter().enumerate() {
+            let error = |reason: &str| ParseError::new(Section::Flags, index, raw_flag, reason);
 
-                let action_type = chars.next_back().expect("Unable to parse flag data");
+            if raw_flag.contains('|') {
+                let mut chars = raw_flag.chars();
+
+                let action_type = chars
+                    .next_back()
+                    .ok_or_else(|| error("missing flag type"))?;
                 let part_one = chars
                     .as_str()
                     .split_once('|')
-                    .expect("Unable to parse flag data");
+                    .ok_or_else(|| error("missing '|'"))?;
                 let part_two = part_one
                     .1
                     .split_once(':')
-                    .expect("Unable to parse flag data");
+                    .ok_or_else(|| error("missing ':'"))?;
+
+                let time = decode_time(part_two.1)
+                    .map_err(|reason| error(&format!("invalid time: {reason}")))?;
 
-                let time = decode(part_two.1);
+                total_time = total_time
+                    .checked_add(time)
+                    .ok_or_else(|| error("total time is too large"))?;
 
                 return_data.push(FlagAction {
-                    x: decode(part_one.0) as i32,
-                    y: decode(part_two.0) as i32,
+                    x: decode_coordinate(part_one.0)
+                        .map_err(|reason| error(&format!("invalid x coordinate: {reason}")))?,
+                    y: decode_coordinate(part_two.0)
+                        .map_err(|reason| error(&format!("invalid y coordinate: {reason}")))?,
                     time,
-                    action: get_flag_type(action_type),
-                    total_time: time + return_data.iter().map(|x| x.time).sum::<i64>(),
+                    action: get_flag_type(action_type).map_err(|reason| error(&reason))?,
+                    total_time,
                 });
             } else {
-                let mut chars = raw_open_field.chars();
-
-                let x = decode(
-                    chars
-                        .next()
-                        .expect("Unable to parse flag data")
-                        .to_string()
-                        .as_str(),
-                ) as i32;
-                let y = decode(
+                let (x, rest) = split_coordinate(raw_flag)
+                    .map_err(|reason| error(&format!("invalid x coordinate: {reason}")))?;
+                let (y, rest) = split_coordinate(rest)
+                    .map_err(|reason| error(&format!("invalid y coordinate: {reason}")))?;
+                let mut chars = rest.chars();
+                let action = get_flag_type(
                     chars
-                        .next()
-                        .expect("Unable to parse flag data")
-                        .to_string()
-                        .as_str(),
-                ) as i32;
-                let action = get_flag_type(chars.next_back().expect("Unable to parse flag data"));
-                let time = decode(chars.as_str());
+                        .next_back()
+                        .ok_or_else(|| error("missing flag type"))?,
+                )
+                .map_err(|reason| error(&reason))?;
+                let time = decode_time(chars.as_str())
+                    .map_err(|reason| error(&format!("invalid time: {reason}")))?;
+
+                total_time = total_time
+                    .checked_add(time)
+                    .ok_or_else(|| error("total time is too large"))?;
 
                 return_data.push(FlagAction {
                     x,
                     y,
                     action,
                     time,
-                    total_time: time + return_data.iter().map(|x| x.time).sum::<i64>(),
+                    total_time,
                 });
             }
         }
 
-        return_data
+        Ok(return_data)
     }
 
-    fn parse_open_data(&self, data: &str) -> Vec<OpenAction> {
-        let mut return_data = Vec::new();
+    fn parse_open_data(&self, data: &str) -> Result<Vec<OpenAction>, ParseError> {
+        parse_open_actions(data, Section::Opens)
+    }
 
-        if data.chars().count() == 0 {
-            return return_data;
-        }
+    fn parse_meta_data(&self, data: &str) -> Result<Metadata, ParseError> {
+        let error = |reason: &str| ParseError::new(Section::Metadata, 0, data, reason);
+
+        let data_split_1 = data.split_once('x').ok_or_else(|| error("missing 'x'"))?;
+        let data_split_2 = data_split_1
+            .1
+            .split_once(',')
+            .ok_or_else(|| error("missing ','"))?;
+        let metadata = Metadata {
+            x_size: i32::from_str(data_split_1.0).map_err(|_| error("invalid width"))?,
+            y_size: i32::from_str(data_split_2.0).map_err(|_| error("invalid height"))?,
+            timeunits: i32::from_str(data_split_2.1).map_err(|_| error("invalid timeunits"))?,
+        };
 
-        let raw_open_fields_data: Vec<&str> = data.split(';').collect();
+        if metadata.x_size <= 0 || metadata.y_size <= 0 {
+            return Err(error("board size must be positive"));
+        }
+        if metadata.timeunits <= 0 {
+            return Err(error("timeunits must be positive"));
+        }
+        if metadata.x_size as i64 * metadata.y_size as i64 > MAX_FIELDS {
+            return Err(error(&format!("board has more than {MAX_FIELDS} fields")));
+        }
 
-        for raw_open_field in raw_open_fields_data {
-            if raw_open_field.contains('|') {
-                let part_one = raw_open_field
-                    .split_once('|')
-                    .expect("Unable to parse open data");
-                let part_two = part_one
-                    .1
-                    .split_once(':')
-                    .expect("Unable to parse open data");
+        Ok(metadata)
+    }
+}
 
-                let time = decode(part_two.1);
+/// Parses `xy<time>` / `x|y:<time>` entries, shared by the opens and the chords of version 3.
+pub(crate) fn parse_open_actions(
+    data: &str,
+    section: Section,
+) -> Result<Vec<OpenAction>, ParseError> {
+    let mut return_data = Vec::new();
+    let mut total_time: i64 = 0;
 
-                return_data.push(OpenAction {
-                    x: decode(part_one.0) as i32,
-                    y: decode(part_two.0) as i32,
-                    time,
-                    total_time: time + return_data.iter().map(|x| x.time).sum::<i64>(),
-                });
-            } else {
-                let mut chars = raw_open_field.chars();
+    if data.chars().count() == 0 {
+        return Ok(return_data);
+    }
 
-                let x = decode(
-                    chars
-                        .next()
-                        .expect("Unable to parse open data")
-                        .to_string()
-                        .as_str(),
-                ) as i32;
-                let y = decode(
-                    chars
-                        .next()
-                        .expect("Unable to parse open data")
-                        .to_string()
-                        .as_str(),
-                ) as i32;
-                let time = decode(chars.as_str());
-
-                return_data.push(OpenAction {
-                    x,
-                    y,
-                    time,
-                    total_time: time + return_data.iter().map(|x| x.time).sum::<i64>(),
-                });
-            }
+    let raw_open_fields_data: Vec<&str> = data.split(';').collect();
+
+    for (index, raw_open_field) in raw_open_fields_data.into_iter().enumerate() {
+        let error = |reason: &str| ParseError::new(section, index, raw_open_field, reason);
+
+        if raw_open_field.contains('|') {
+            let part_one = raw_open_field
+                .split_once('|')
+                .ok_or_else(|| error("missing '|'"))?;
+            let part_two = part_one
+                .1
+                .split_once(':')
+                .ok_or_else(|| error("missing ':'"))?;
+
+            let time = decode_time(part_two.1)
+                .map_err(|reason| error(&format!("invalid time: {reason}")))?;
+
+            total_time = total_time
+                .checked_add(time)
+                .ok_or_else(|| error("total time is too large"))?;
+
+            return_data.push(OpenAction {
+                x: decode_coordinate(part_one.0)
+                    .map_err(|reason| error(&format!("invalid x coordinate: {reason}")))?,
+                y: decode_coordinate(part_two.0)
+                    .map_err(|reason| error(&format!("invalid y coordinate: {reason}")))?,
+                time,
+                total_time,
+            });
+        } else {
+            let (x, rest) = split_coordinate(raw_open_field)
+                .map_err(|reason| error(&format!("invalid x coordinate: {reason}")))?;
+            let (y, rest) = split_coordinate(rest)
+                .map_err(|reason| error(&format!("invalid y coordinate: {reason}")))?;
+            let time =
+                decode_time(rest).map_err(|reason| error(&format!("invalid time: {reason}")))?;
+
+            total_time = total_time
+                .checked_add(time)
+                .ok_or_else(|| error("total time is too large"))?;
+
+            return_data.push(OpenAction {
+                x,
+                y,
+                time,
+                total_time,
+            });
         }
+    }
+
+    Ok(return_data)
+}
 
-        return_data
+fn split_coordinate(data: &str) -> Result<(i32, &str), String> {
+    let (coordinate, rest) = decode_fixed(data, 1).map_err(|error| error.to_string())?;
+    Ok((coordinate as i32, rest))
+}
+
+fn decode_coordinate(data: &str) -> Result<i32, String> {
+    let coordinate = decode(data).map_err(|error| error.to_string())?;
+    i32::try_from(coordinate).map_err(|_| format!("{coordinate} is too large"))
+}
+
+fn decode_time(data: &str) -> Result<i64, String> {
+    decode(data).map_err(|error| error.to_string())
+}
+
+fn get_flag_type(raw_flag_type: char) -> Result<Action, String> {
+    match raw_flag_type {
+        'P' => Ok(Action::Place),
+        'R' => Ok(Action::Remove),
+        'T' => Ok(Action::Toggle),
+        other => Err(format!("unknown flag type '{other}'")),
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::MinesweeperError;
+    use crate::parsers::registry::ParserRegistry;
 
-    fn parse_meta_data(&self, data: &str) -> Metadata {
-        let data_split_1 = data.split_once('x').expect("Unable to parse Metadata");
-        let data_split_2 = data_split_1.1.split_once(',').expect("Unable to parse Metadata");
-        Metadata {
-            x_size: i32::from_str(data_split_1.0).expect("Unable to parse Metadata"),
-            y_size: i32::from_str(data_split_2.0).expect("Unable to parse Metadata"),
-            timeunits: i32::from_str(data_split_2.1).expect("Unable to parse Metadata"),
+    #[test]
+    fn rejects_overflowing_total_time() {
+        let max = crate::base62::encode(i64::MAX as u64);
+        let game = format!("2=4x2,50+00+11{max};22{max}+");
+
+        match ParserRegistry::default().parse(&game) {
+            Err(MinesweeperError::Parse(error)) => {
+                assert_eq!(error.index, 1);
+                assert_eq!(error.reason, "total time is too large");
+            }
+            other => panic!("expected a parse error, got {other:?}"),
         }
     }
-}
 
-fn get_flag_type(raw_flag_type: char) -> Action {
-    match raw_flag_type {
-        'P' => Action::Place,
-        'R' => Action::Remove,
-        'T' => Action::Toggle,
-        _ => unreachable!(),
+    #[test]
+    fn rejects_non_positive_timeunits() {
+        for game in ["2=4x2,-50+0010+11z+", "2=4x2,0+0010+11z+"] {
+            match ParserRegistry::default().parse(game) {
+                Err(MinesweeperError::Parse(error)) => {
+                    assert_eq!(error.reason, "timeunits must be positive")
+                }
+                other => panic!("expected a parse error, got {other:?}"),
+            }
+        }
     }
 }
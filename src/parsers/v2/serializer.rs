@@ -0,0 +1,174 @@
+use crate::base62::{encode, encode_fixed};
+use crate::error::{MinesweeperError, Section};
+use crate::parsers::parser::{Action, FlagAction, Metadata, OpenAction, ParsedData};
+use crate::parsers::serializer::Iserializer;
+
+pub struct SerializerV2;
+
+impl Iserializer for SerializerV2 {
+    fn version(&self) -> &str {
+        "2"
+    }
+
+    fn serialize(&self, data: &ParsedData) -> Result<String, MinesweeperError> {
+        if !data.chord_data.is_empty() {
+            return Err(MinesweeperError::Serialize {
+                section: Section::Chords,
+                index: 0,
+                reason: "chords need version 3".to_string(),
+            });
+        }
+
+        Ok(format!(
+            "{}={}+{}+{}+{}",
+            self.version(),
+            serialize_meta_data(&data.metadata),
+            serialize_mine_locations(&mine_locations(data))?,
+            serialize_open_data(&data.open_data, Section::Opens)?,
+            serialize_flag_data(&data.flag_data)?,
+        ))
+    }
+}
+
+pub(crate) fn mine_locations(data: &ParsedData) -> Vec<(i32, i32)> {
+    data.game_board
+        .fields
+        .iter()
+        .filter(|(_, field)| field.mine)
+        .map(|(coord, _)| (coord.x as i32, coord.y as i32))
+        .collect()
+}
+
+pub(crate) fn serialize_meta_data(metadata: &Metadata) -> String {
+    format!(
+        "{}x{},{}",
+        metadata.x_size, metadata.y_size, metadata.timeunits
+    )
+}
+
+pub(crate) fn serialize_mine_locations(mines: &[(i32, i32)]) -> Result<String, MinesweeperError> {
+    let mut compact = String::new();
+    let mut entries = Vec::new();
+
+    for (index, &(x, y)) in mines.iter().enumerate() {
+        match encode_position(x, y, Section::Mines, index)? {
+            EncodedPosition::Compact(position) => compact.push_str(&position),
+            EncodedPosition::Long(x, y) => entries.push(format!("{x}|{y}")),
+        }
+    }
+
+    if !compact.is_empty() {
+        entries.insert(0, compact);
+    }
+
+    Ok(entries.join(";"))
+}
+
+/// Also writes the chords of version 3, which share the encoding of the opens.
+pub(crate) fn serialize_open_data(
+    open_data: &[OpenAction],
+    section: Section,
+) -> Result<String, MinesweeperError> {
+    let mut entries = Vec::new();
+
+    for (index, action) in open_data.iter().enumerate() {
+        entries.push(serialize_position(
+            action.x,
+            action.y,
+            action.time,
+            section,
+            index,
+        )?);
+    }
+
+    Ok(entries.join(";"))
+}
+
+pub(crate) fn serialize_flag_data(flag_data: &[FlagAction]) -> Result<String, MinesweeperError> {
+    let mut entries = Vec::new();
+
+    for (index, action) in flag_data.iter().enumerate() {
+        let mut entry = serialize_position(action.x, action.y, action.time, Section::Flags, index)?;
+        entry.push(match action.action {
+            Action::Place => 'P',
+            Action::Remove => 'R',
+            Action::Toggle => 'T',
+        });
+        entries.push(entry);
+    }
+
+    Ok(entries.join(";"))
+}
+
+/// Writes the shared `xy<time>` / `x|y:<time>` part of an open or flag entry.
+fn serialize_position(
+    x: i32,
+    y: i32,
+    time: i64,
+    section: Section,
+    index: usize,
+) -> Result<String, MinesweeperError> {
+    let time = u64::try_from(time).map_err(|_| MinesweeperError::Serialize {
+        section,
+        index,
+        reason: format!("negative time {time}"),
+    })?;
+    let time = encode(time);
+
+    Ok(match encode_position(x, y, section, index)? {
+        EncodedPosition::Compact(position) => format!("{position}{time}"),
+        EncodedPosition::Long(x, y) => format!("{x}|{y}:{time}"),
+    })
+}
+
+pub(crate) enum EncodedPosition {
+    /// Both coordinates fit into a single character each.
+    Compact(String),
+    Long(String, String),
+}
+
+pub(crate) fn encode_position(
+    x: i32,
+    y: i32,
+    section: Section,
+    index: usize,
+) -> Result<EncodedPosition, MinesweeperError> {
+    let unsigned = |coordinate: i32| {
+        u64::try_from(coordinate).map_err(|_| MinesweeperError::Serialize {
+            section,
+            index,
+            reason: format!("negative coordinate {coordinate}"),
+        })
+    };
+    let x = unsigned(x)?;
+    let y = unsigned(y)?;
+
+    Ok(match (encode_fixed(x, 1), encode_fixed(y, 1)) {
+        (Ok(x), Ok(y)) => EncodedPosition::Compact(x + &y),
+        _ => EncodedPosition::Long(encode(x), encode(y)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::registry::{ParserRegistry, SerializerRegistry};
+
+    fn round_trip(game: &str) -> String {
+        let data = ParserRegistry::default().parse(game).unwrap();
+        let serialized = SerializerRegistry::default().serialize(&data, "2").unwrap();
+        assert_eq!(ParserRegistry::default().parse(&serialized).unwrap(), data);
+        serialized
+    }
+
+    #[test]
+    fn compact_entries_round_trip() {
+        let game = "2=4x2,50+0010+11A;21B;301+00CP;00DR";
+        assert_eq!(round_trip(game), game);
+    }
+
+    #[test]
+    fn long_entries_round_trip() {
+        let game = "2=80x64,20+0010;1A|1;3|11+1A|0:5;22A;z|11:1B+1B|2:3T";
+        assert_eq!(round_trip(game), game);
+    }
+}
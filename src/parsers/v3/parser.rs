@@ -0,0 +1,37 @@
+use crate::error::{ParseError, Section};
+use crate::minesweeper_logic::Board;
+use crate::parsers::parser::{FlagAction, Iparser, Metadata, OpenAction};
+use crate::parsers::v2::parser::{parse_open_actions, ParserV2};
+
+/// Version 3 is version 2 with an additional chord section, encoded like the opens.
+pub struct ParserV3;
+
+impl Iparser for ParserV3 {
+    fn supported_versions(&self) -> Vec<&str> {
+        vec!["3"]
+    }
+
+    fn parse_mine_data(&self, data: &str, metadata: &Metadata) -> Result<Board, ParseError> {
+        ParserV2.parse_mine_data(data, metadata)
+    }
+
+    fn parse_mine_locations(&self, data: &str) -> Result<Vec<(i32, i32)>, ParseError> {
+        ParserV2.parse_mine_locations(data)
+    }
+
+    fn parse_flag_data(&self, data: &str) -> Result<Vec<FlagAction>, ParseError> {
+        ParserV2.parse_flag_data(data)
+    }
+
+    fn parse_open_data(&self, data: &str) -> Result<Vec<OpenAction>, ParseError> {
+        ParserV2.parse_open_data(data)
+    }
+
+    fn parse_meta_data(&self, data: &str) -> Result<Metadata, ParseError> {
+        ParserV2.parse_meta_data(data)
+    }
+
+    fn parse_chord_data(&self, data: &str) -> Result<Vec<OpenAction>, ParseError> {
+        parse_open_actions(data, Section::Chords)
+    }
+}
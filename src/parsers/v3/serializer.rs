@@ -0,0 +1,57 @@
+use crate::error::{MinesweeperError, Section};
+use crate::parsers::parser::ParsedData;
+use crate::parsers::serializer::Iserializer;
+use crate::parsers::v2::serializer::{
+    mine_locations, serialize_flag_data, serialize_meta_data, serialize_mine_locations,
+    serialize_open_data,
+};
+
+pub struct SerializerV3;
+
+impl Iserializer for SerializerV3 {
+    fn version(&self) -> &str {
+        "3"
+    }
+
+    /// The chord section is optional and left out if there are no chords.
+    fn serialize(&self, data: &ParsedData) -> Result<String, MinesweeperError> {
+        let mut serialized = format!(
+            "{}={}+{}+{}+{}",
+            self.version(),
+            serialize_meta_data(&data.metadata),
+            serialize_mine_locations(&mine_locations(data))?,
+            serialize_open_data(&data.open_data, Section::Opens)?,
+            serialize_flag_data(&data.flag_data)?,
+        );
+        if !data.chord_data.is_empty() {
+            serialized.push('+');
+            serialized.push_str(&serialize_open_data(&data.chord_data, Section::Chords)?);
+        }
+
+        Ok(serialized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parsers::registry::{ParserRegistry, SerializerRegistry};
+
+    fn round_trip(game: &str) -> String {
+        let data = ParserRegistry::default().parse(game).unwrap();
+        let serialized = SerializerRegistry::default().serialize(&data, "3").unwrap();
+        assert_eq!(ParserRegistry::default().parse(&serialized).unwrap(), data);
+        serialized
+    }
+
+    #[test]
+    fn chord_entries_round_trip() {
+        let game = "3=80x64,20+0010;1A|1+22A;1A|0:5+00BP+11C;z|11:D";
+        assert_eq!(round_trip(game), game);
+    }
+
+    #[test]
+    fn game_without_chords_round_trips() {
+        let game = "3=4x2,50+0010+11A;21B+00CP";
+        assert_eq!(round_trip(game), game);
+    }
+}
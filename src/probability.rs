@@ -0,0 +1,449 @@
+use crate::grid::{Coord, Grid};
+use crate::minesweeper_logic::{Board, FieldState};
+use crate::solver::{constraints, deduce, Constraint, Deduced};
+
+/// Maximum number of search steps spent enumerating the frontier components of a board. Smaller
+/// components are enumerated first, the ones left when the budget runs out are sampled.
+const ENUMERATION_BUDGET: usize = 1_000_000;
+/// Maximum number of search steps spent sampling, shared by all sampled components.
+const SAMPLING_BUDGET: usize = 1_000_000;
+
+/// The chance of every closed field to contain a mine.
+pub struct Probabilities {
+    /// `None` for open fields.
+    pub fields: Grid<Option<f64>>,
+    /// `false` if at least one area was too large to enumerate and got estimated by sampling.
+    pub exact: bool,
+}
+
+/// Calculates the mine probability of every closed field from the visible numbers and the
+/// total mine count, assuming every consistent mine layout is equally likely.
+pub fn mine_probabilities(board: &Board) -> Probabilities {
+    let known = deduce(board);
+    let fields = &board.fields;
+    let mut probabilities = Grid::new(fields.width(), fields.height(), None);
+
+    for (coord, deduced) in known.iter() {
+        if fields[coord].field_state == FieldState::Open {
+            continue;
+        }
+        probabilities[coord] = match deduced {
+            Deduced::Safe => Some(0.0),
+            Deduced::Mine => Some(1.0),
+            Deduced::Unknown => None,
+        };
+    }
+
+    let found_mines = known.iter().filter(|(_, &d)| d == Deduced::Mine).count();
+    let remaining_mines = (board.mine_count as usize).saturating_sub(found_mines);
+
+    let constraints = constraints(board, &known);
+    let components = components(&constraints);
+    let frontier: Vec<Coord> = components.iter().flat_map(|c| c.cells.clone()).collect();
+    let interior: Vec<Coord> = known
+        .iter()
+        .filter(|(coord, &d)| d == Deduced::Unknown && !frontier.contains(coord))
+        .map(|(coord, _)| coord)
+        .collect();
+
+    let mut order: Vec<usize> = (0..components.len()).collect();
+    order.sort_by_key(|&index| components[index].cells.len());
+    let mut steps = 0;
+    let mut enumerated: Vec<Option<Solutions>> = components.iter().map(|_| None).collect();
+    for index in order {
+        enumerated[index] = components[index].enumerate(&mut steps);
+    }
+
+    let sampled = enumerated.iter().filter(|s| s.is_none()).count();
+    let exact = sampled == 0;
+    let mut random = XorShift(0x2545_f491_4f6c_dd1d);
+    let solutions: Vec<Solutions> = enumerated
+        .into_iter()
+        .zip(&components)
+        .map(|(solutions, component)| {
+            solutions.unwrap_or_else(|| component.sample(SAMPLING_BUDGET / sampled, &mut random))
+        })
+        .collect();
+
+    let weights = Weights::new(interior.len(), remaining_mines, frontier.len());
+    let all = convolve_all(solutions.iter().map(|s| s.totals()));
+    let total = weights.total(&all);
+
+    if total > 0.0 {
+        for (index, (component, solution)) in components.iter().zip(&solutions).enumerate() {
+            let others = convolve_all(
+                solutions
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(_, s)| s.totals()),
+            );
+
+            for (cell_index, &coord) in component.cells.iter().enumerate() {
+                let mut weight = 0.0;
+                for (mines, counts) in solution.cell_counts.iter().enumerate() {
+                    if counts[cell_index] == 0.0 {
+                        continue;
+                    }
+                    let shifted: Vec<f64> = (0..mines)
+                        .map(|_| 0.0)
+                        .chain(others.iter().copied())
+                        .collect();
+                    weight += counts[cell_index] * weights.total(&shifted);
+                }
+                probabilities[coord] = Some(weight / total);
+            }
+        }
+
+        if !interior.is_empty() {
+            let interior_mines: f64 = all
+                .iter()
+                .enumerate()
+                .map(|(frontier_mines, &count)| {
+                    count
+                        * weights.binomial(remaining_mines.checked_sub(frontier_mines))
+                        * remaining_mines.saturating_sub(frontier_mines) as f64
+                })
+                .sum();
+            let probability = interior_mines / total / interior.len() as f64;
+            for &coord in &interior {
+                probabilities[coord] = Some(probability);
+            }
+        }
+    }
+
+    Probabilities {
+        fields: probabilities,
+        exact,
+    }
+}
+
+/// Unknown fields that are linked through shared constraints.
+struct Component {
+    cells: Vec<Coord>,
+    /// Constraints as indices into `cells`.
+    constraints: Vec<(Vec<usize>, usize)>,
+}
+
+fn components(constraints: &[Constraint]) -> Vec<Component> {
+    let mut assigned: Vec<Option<usize>> = vec![None; constraints.len()];
+    let mut components = Vec::new();
+
+    for start in 0..constraints.len() {
+        if assigned[start].is_some() {
+            continue;
+        }
+
+        let id = components.len();
+        let mut cells: Vec<Coord> = Vec::new();
+        let mut members = vec![start];
+        let mut stack = vec![start];
+        assigned[start] = Some(id);
+
+        while let Some(current) = stack.pop() {
+            for &cell in &constraints[current].cells {
+                if !cells.contains(&cell) {
+                    cells.push(cell);
+                }
+            }
+
+            for (other, constraint) in constraints.iter().enumerate() {
+                if assigned[other].is_none() && constraint.cells.iter().any(|c| cells.contains(c)) {
+                    assigned[other] = Some(id);
+                    members.push(other);
+                    stack.push(other);
+                }
+            }
+        }
+
+        let constraints = members
+            .iter()
+            .map(|&member| {
+                let constraint = &constraints[member];
+                let indices = constraint
+                    .cells
+                    .iter()
+                    .map(|c| cells.iter().position(|cell| cell == c).unwrap())
+                    .collect();
+                (indices, constraint.mines)
+            })
+            .collect();
+
+        components.push(Component { cells, constraints });
+    }
+
+    components
+}
+
+/// Consistent mine layouts of a component, grouped by their number of mines.
+struct Solutions {
+    /// `cell_counts[k][i]` is the number of layouts with `k` mines where cell `i` is a mine.
+    cell_counts: Vec<Vec<f64>>,
+    /// `counts[k]` is the number of layouts with `k` mines.
+    counts: Vec<f64>,
+}
+
+impl Solutions {
+    fn new(cells: usize) -> Self {
+        Solutions {
+            cell_counts: vec![vec![0.0; cells]; cells + 1],
+            counts: vec![0.0; cells + 1],
+        }
+    }
+
+    fn record(&mut self, layout: &[bool]) {
+        self.record_weighted(layout, 1.0);
+    }
+
+    fn record_weighted(&mut self, layout: &[bool], weight: f64) {
+        let mines = layout.iter().filter(|&&mine| mine).count();
+        self.counts[mines] += weight;
+        for (cell, &mine) in layout.iter().enumerate() {
+            if mine {
+                self.cell_counts[mines][cell] += weight;
+            }
+        }
+    }
+
+    fn totals(&self) -> Vec<f64> {
+        self.counts.clone()
+    }
+}
+
+impl Component {
+    /// Enumerates every consistent layout, `None` if `steps` exceeds [`ENUMERATION_BUDGET`].
+    fn enumerate(&self, steps: &mut usize) -> Option<Solutions> {
+        let mut solutions = Solutions::new(self.cells.len());
+        let mut search = Search::new(self);
+
+        search.run(&mut |layout| solutions.record(layout), steps)?;
+        Some(solutions)
+    }
+
+    /// Estimates the layout counts with Knuth's estimator within `budget` search steps.
+    ///
+    /// Every probe assigns the cells in order and picks one of the consistent values at random.
+    /// A complete layout is recorded with the product of the number of choices on its path, a
+    /// probe that runs into a contradiction counts as zero. The expected weight of every layout
+    /// is one, so the recorded counts are unbiased estimates of the real ones.
+    fn sample(&self, budget: usize, random: &mut XorShift) -> Solutions {
+        let mut solutions = Solutions::new(self.cells.len());
+        let mut search = Search::new(self);
+        // Weights can exceed the range of f64, so they are kept as logarithms until the end
+        let mut probes: Vec<(f64, Vec<bool>)> = Vec::new();
+        let mut steps = 0;
+
+        while steps + self.cells.len() <= budget {
+            if let Some(probe) = search.probe(random, &mut steps) {
+                probes.push(probe);
+            }
+        }
+
+        let ln_max = probes.iter().map(|(ln, _)| *ln).fold(f64::MIN, f64::max);
+        for (ln_weight, layout) in &probes {
+            solutions.record_weighted(layout, (ln_weight - ln_max).exp());
+        }
+        solutions
+    }
+}
+
+/// Backtracking search over the mine layouts of a component.
+struct Search<'a> {
+    component: &'a Component,
+    /// Constraints every cell is part of.
+    cell_constraints: Vec<Vec<usize>>,
+    layout: Vec<Option<bool>>,
+}
+
+impl<'a> Search<'a> {
+    fn new(component: &'a Component) -> Self {
+        let mut cell_constraints = vec![Vec::new(); component.cells.len()];
+        for (id, (cells, _)) in component.constraints.iter().enumerate() {
+            for &cell in cells {
+                cell_constraints[cell].push(id);
+            }
+        }
+
+        Search {
+            component,
+            cell_constraints,
+            layout: vec![None; component.cells.len()],
+        }
+    }
+
+    /// Calls `found` for every complete layout, `None` if `steps` exceeds the budget.
+    fn run(&mut self, found: &mut dyn FnMut(&[bool]), steps: &mut usize) -> Option<()> {
+        *steps += 1;
+        if *steps > ENUMERATION_BUDGET {
+            return None;
+        }
+
+        let Some(cell) = self.layout.iter().position(|value| value.is_none()) else {
+            let layout: Vec<bool> = self.layout.iter().map(|v| v.unwrap_or(false)).collect();
+            found(&layout);
+            return Some(());
+        };
+
+        for value in [false, true] {
+            self.layout[cell] = Some(value);
+            if self.is_consistent(cell) {
+                self.run(found, steps)?;
+            }
+        }
+
+        self.layout[cell] = None;
+        Some(())
+    }
+
+    /// Builds one random layout, returning it with the logarithm of its weight, or `None` if
+    /// the probe ran into a contradiction.
+    fn probe(&mut self, random: &mut XorShift, steps: &mut usize) -> Option<(f64, Vec<bool>)> {
+        self.layout.fill(None);
+        let mut ln_weight = 0.0;
+
+        for cell in 0..self.layout.len() {
+            *steps += 1;
+            let mut choices = Vec::with_capacity(2);
+            for value in [false, true] {
+                self.layout[cell] = Some(value);
+                if self.is_consistent(cell) {
+                    choices.push(value);
+                }
+            }
+
+            let choice = match choices[..] {
+                [] => return None,
+                [value] => value,
+                _ => {
+                    ln_weight += std::f64::consts::LN_2;
+                    choices[(random.next() % 2) as usize]
+                }
+            };
+            self.layout[cell] = Some(choice);
+        }
+
+        Some((
+            ln_weight,
+            self.layout.iter().map(|v| *v == Some(true)).collect(),
+        ))
+    }
+
+    fn is_consistent(&self, cell: usize) -> bool {
+        self.cell_constraints[cell].iter().all(|&id| {
+            let (cells, mines) = &self.component.constraints[id];
+            let placed = cells
+                .iter()
+                .filter(|&&c| self.layout[c] == Some(true))
+                .count();
+            let open = cells.iter().filter(|&&c| self.layout[c].is_none()).count();
+            placed <= *mines && placed + open >= *mines
+        })
+    }
+}
+
+/// Combines the layout counts of the frontier with the ways to place the remaining mines in the
+/// interior fields.
+struct Weights {
+    interior: usize,
+    remaining_mines: usize,
+    ln_factorials: Vec<f64>,
+    /// Subtracted from every logarithm to keep the weights inside the range of `f64`.
+    ln_scale: f64,
+}
+
+impl Weights {
+    fn new(interior: usize, remaining_mines: usize, frontier: usize) -> Self {
+        let mut ln_factorials = vec![0.0; interior + 1];
+        for n in 1..=interior {
+            ln_factorials[n] = ln_factorials[n - 1] + (n as f64).ln();
+        }
+
+        let mut weights = Weights {
+            interior,
+            remaining_mines,
+            ln_factorials,
+            ln_scale: 0.0,
+        };
+        weights.ln_scale = (0..=frontier.min(remaining_mines))
+            .filter_map(|frontier_mines| weights.ln_binomial(remaining_mines - frontier_mines))
+            .fold(f64::MIN, f64::max);
+        weights
+    }
+
+    fn ln_binomial(&self, mines: usize) -> Option<f64> {
+        (mines <= self.interior).then(|| {
+            self.ln_factorials[self.interior]
+                - self.ln_factorials[mines]
+                - self.ln_factorials[self.interior - mines]
+        })
+    }
+
+    /// Scaled number of ways to place `mines` mines in the interior.
+    fn binomial(&self, mines: Option<usize>) -> f64 {
+        mines
+            .and_then(|mines| self.ln_binomial(mines))
+            .map(|ln| (ln - self.ln_scale).exp())
+            .unwrap_or(0.0)
+    }
+
+    /// Sum over all frontier mine counts, `counts[k]` being the layouts with `k` frontier mines.
+    fn total(&self, counts: &[f64]) -> f64 {
+        counts
+            .iter()
+            .enumerate()
+            .map(|(mines, &count)| count * self.binomial(self.remaining_mines.checked_sub(mines)))
+            .sum()
+    }
+}
+
+fn convolve_all(distributions: impl Iterator<Item = Vec<f64>>) -> Vec<f64> {
+    distributions.fold(vec![1.0], |acc, distribution| {
+        let mut result = vec![0.0; acc.len() + distribution.len() - 1];
+        for (i, &a) in acc.iter().enumerate() {
+            for (j, &b) in distribution.iter().enumerate() {
+                result[i + j] += a * b;
+            }
+        }
+        result
+    })
+}
+
+/// Small deterministic random number generator for sampling.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marginals(solutions: &Solutions) -> Vec<f64> {
+        let total: f64 = solutions.counts.iter().sum();
+        (0..solutions.counts.len() - 1)
+            .map(|cell| solutions.cell_counts.iter().map(|c| c[cell]).sum::<f64>() / total)
+            .collect()
+    }
+
+    #[test]
+    fn sampling_matches_enumeration() {
+        // Layouts of different sizes, so a sampler biased towards some of them shows
+        let component = Component {
+            cells: (0..5).map(|x| Coord::new(x, 0)).collect(),
+            constraints: vec![(vec![0, 1], 1), (vec![1, 2], 1), (vec![2, 3, 4], 2)],
+        };
+
+        let exact = marginals(&component.enumerate(&mut 0).unwrap());
+        let sampled = marginals(&component.sample(200_000, &mut XorShift(0x2545_f491_4f6c_dd1d)));
+
+        for (exact, sampled) in exact.iter().zip(&sampled) {
+            assert!((exact - sampled).abs() < 0.02, "{exact} != {sampled}");
+        }
+    }
+}
@@ -1,46 +1,41 @@
-use std::collections::BTreeMap;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, Cursor, Write};
+use std::path::Path;
 use std::time::Duration;
 
-use clap::Parser;
 use gif::{Encoder, Frame as GifFrame, Repeat};
-use image::{Delay, Frame, GenericImage, ImageBuffer, Rgba};
+use image::{
+    Delay, DynamicImage, Frame, GenericImage, GenericImageView, ImageBuffer, ImageOutputFormat,
+    Rgba,
+};
 
 use crate::error::MinesweeperError;
-use crate::minesweeper_logic::{Board, FieldState};
-use crate::parsers::parser::{ActionType, FlagAction, Metadata, OpenAction};
+use crate::grid::{Coord, Grid};
+use crate::minesweeper_logic::FieldState;
+use crate::parsers::parser::{Metadata, ParsedData};
+use crate::probability::mine_probabilities;
+use crate::replay::Replay;
+use crate::solver::{ClickAnalysis, ClickKind};
 
 const BAR_LENGTH: usize = 50;
-
-pub struct Renderer<'a> {
-    pub(crate) metadata: Metadata,
-    game_board: Board,
-    open_data: Vec<OpenAction>,
-    flag_data: Vec<FlagAction>,
+/// Height in pixels of the progress bar below the board.
+const PROGRESSBAR_HEIGHT: u32 = 4;
+
+/// Renders a game either as a single image of the final board or as a GIF replay.
+pub struct Renderer {
+    pub metadata: Metadata,
+    replay: Replay,
+    /// Clicks that get outlined when their field is drawn.
+    highlights: Grid<Option<ClickKind>>,
+    /// Tint closed fields by their mine probability.
+    heatmap: bool,
+    /// Frames whose probabilities were estimated by sampling instead of calculated exactly.
+    estimated_frames: usize,
+    /// Print a progress bar to stderr while rendering.
+    progress: bool,
+    /// Render GIF output as a still image of the end instead of a replay.
+    still: bool,
     image_data: Imagedata,
-    encoder: Option<Encoder<File>>,
-    options: &'a RenderOptions,
-}
-
-#[derive(Parser)]
-#[command()]
-pub struct RenderOptions {
-    #[arg(
-        short,
-        long,
-        value_enum,
-        help = "Choose either 'image' or 'gif' to force that type to be generated. If not set, it will choose automatically based on the size."
-    )]
-    pub(crate) force_type: Option<RenderType>,
-    #[arg(
-        long,
-        help = "To render the GIF or Image with a custom texture set the path relativ to the executable"
-    )]
-    pub(crate) custom_textures: Option<String>,
-    #[arg(short, long, help = "Enable this if you want to insert data yourself.")]
-    pub custom_input: bool,
-    #[arg(short, long, help = "Should the GIF repeat?")]
     repeat: bool,
 }
 
@@ -50,6 +45,17 @@ pub enum RenderType {
     Gif,
 }
 
+impl RenderType {
+    /// Big boards are rendered as an image since a GIF of them would get too large.
+    pub fn for_metadata(metadata: &Metadata) -> RenderType {
+        if metadata.x_size >= 32 || metadata.y_size >= 32 {
+            RenderType::Image
+        } else {
+            RenderType::Gif
+        }
+    }
+}
+
 impl std::str::FromStr for RenderType {
     type Err = String;
 
@@ -62,6 +68,62 @@ impl std::str::FromStr for RenderType {
     }
 }
 
+/// The file format a render gets written as.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Png,
+    Jpeg,
+    WebP,
+    Gif,
+}
+
+impl OutputFormat {
+    /// Infers the format from the extension of `path`.
+    pub fn from_path(path: &str) -> Option<OutputFormat> {
+        Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str()?.parse().ok())
+    }
+
+    /// The default format for a render type.
+    pub fn for_render_type(render_type: RenderType) -> OutputFormat {
+        match render_type {
+            RenderType::Image => OutputFormat::WebP,
+            RenderType::Gif => OutputFormat::Gif,
+        }
+    }
+
+    pub fn render_type(self) -> RenderType {
+        match self {
+            OutputFormat::Gif => RenderType::Gif,
+            _ => RenderType::Image,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::WebP => "webp",
+            OutputFormat::Gif => "gif",
+        }
+    }
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_ref() {
+            "png" => Ok(OutputFormat::Png),
+            "jpg" | "jpeg" => Ok(OutputFormat::Jpeg),
+            "webp" => Ok(OutputFormat::WebP),
+            "gif" => Ok(OutputFormat::Gif),
+            _ => Err(format!("Unknown output format: {}", s)),
+        }
+    }
+}
+
 struct Imagedata {
     zero: ImageBuffer<Rgba<u8>, Vec<u8>>,
     one: ImageBuffer<Rgba<u8>, Vec<u8>>,
@@ -78,9 +140,24 @@ struct Imagedata {
     unsure_flag: ImageBuffer<Rgba<u8>, Vec<u8>>,
 }
 
+/// Edge length of a single texture on the sprite sheet.
+const TEXTURE_SIZE: u32 = 32;
+/// Number of textures placed next to each other on the sprite sheet.
+const TEXTURE_COUNT: u32 = 13;
+
 impl Imagedata {
-    pub fn new(sprite_data: &[u8]) -> Imagedata {
-        let im = &mut image::load_from_memory(sprite_data).expect("Custom Textures file not found");
+    pub fn new(sprite_data: &[u8]) -> Result<Imagedata, MinesweeperError> {
+        let im = &mut image::load_from_memory(sprite_data)
+            .map_err(|e| MinesweeperError::InvalidTextures(e.to_string()))?;
+
+        let (width, height) = im.dimensions();
+        if width < TEXTURE_SIZE * TEXTURE_COUNT || height < TEXTURE_SIZE {
+            return Err(MinesweeperError::InvalidTextures(format!(
+                "the sprite sheet is {width}x{height} pixels but needs at least {}x{}",
+                TEXTURE_SIZE * TEXTURE_COUNT,
+                TEXTURE_SIZE
+            )));
+        }
 
         let zero = im.sub_image(0, 0, 32, 32).to_image();
         let one = im.sub_image(32, 0, 32, 32).to_image();
@@ -96,7 +173,7 @@ impl Imagedata {
         let flag = im.sub_image(32 * 11, 0, 32, 32).to_image();
         let unsure_flag = im.sub_image(32 * 12, 0, 32, 32).to_image();
 
-        Imagedata {
+        Ok(Imagedata {
             zero,
             one,
             two,
@@ -110,167 +187,222 @@ impl Imagedata {
             empty,
             flag,
             unsure_flag,
-        }
+        })
     }
 }
 
-impl<'a> Renderer<'a> {
+impl Renderer {
+    /// `sprite_data` is the encoded texture sheet, see [`crate::textures::load_textures`].
+    /// `repeat` controls whether a rendered GIF loops. Fails if the sprite sheet cannot be
+    /// decoded or is too small to hold every texture.
     pub fn new(
-        metadata: Metadata,
-        game_board: Board,
-        open_data: Vec<OpenAction>,
-        flag_data: Vec<FlagAction>,
+        data: ParsedData,
         sprite_data: &[u8],
-        options: &'a RenderOptions,
-    ) -> Renderer<'a> {
-        Renderer {
-            metadata,
-            game_board,
-            open_data,
-            flag_data,
-            image_data: Imagedata::new(sprite_data),
-            encoder: None,
-            options,
+        repeat: bool,
+    ) -> Result<Renderer, MinesweeperError> {
+        Ok(Renderer {
+            metadata: data.metadata.clone(),
+            highlights: Grid::new(
+                data.metadata.x_size as usize,
+                data.metadata.y_size as usize,
+                None,
+            ),
+            heatmap: false,
+            estimated_frames: 0,
+            progress: true,
+            still: false,
+            replay: Replay::new(data),
+            image_data: Imagedata::new(sprite_data)?,
+            repeat,
+        })
+    }
+
+    /// Outlines guessed and blundered clicks from a [`crate::solver::analyse`] result.
+    pub fn highlight_clicks(&mut self, analysis: &[ClickAnalysis]) {
+        for click in analysis {
+            if click.kind != ClickKind::Forced {
+                if let Some(highlight) = self.highlights.get_mut(click.coord) {
+                    *highlight = Some(click.kind);
+                }
+            }
         }
     }
 
-    pub fn render_jpeg(&mut self) -> Result<(), MinesweeperError> {
-        self.flag_data
-            .iter()
-            .for_each(|action| action.perform_action(&mut self.game_board));
+    /// Colours every closed field from green to red by its chance of being a mine at the time
+    /// of the frame, see [`crate::probability::mine_probabilities`].
+    pub fn show_probabilities(&mut self) {
+        self.heatmap = true;
+    }
+
+    /// Number of rendered frames whose probabilities are estimates, see
+    /// [`crate::probability::Probabilities::exact`].
+    pub fn estimated_frames(&self) -> usize {
+        self.estimated_frames
+    }
+
+    /// Renders without printing a progress bar, e.g. when rendering several games at once.
+    pub fn hide_progress(&mut self) {
+        self.progress = false;
+    }
 
-        self.open_data.iter().for_each(|action| {
-            self.game_board
-                .open_field(action.x as usize, action.y as usize);
-        });
+    /// Renders a still image of the end even if the format is GIF.
+    pub fn still_image(&mut self) {
+        self.still = true;
+    }
 
-        let percentage_done = self.game_board.calculate_done_percentage();
+    /// Renders the game with `format` into the file at `path`, `-` writes to stdout.
+    pub fn render_to_path(
+        &mut self,
+        path: &str,
+        format: OutputFormat,
+    ) -> Result<(), MinesweeperError> {
+        self.render(open_output(path)?, format)
+    }
+
+    /// Renders the game with `format` into memory.
+    pub fn render_to_vec(&mut self, format: OutputFormat) -> Result<Vec<u8>, MinesweeperError> {
+        let mut buffer = Vec::new();
+        self.render(&mut buffer, format)?;
+        Ok(buffer)
+    }
+
+    /// Renders the game with `format` into `writer`, a GIF replay or a still image of the end.
+    pub fn render<W: Write>(
+        &mut self,
+        writer: W,
+        format: OutputFormat,
+    ) -> Result<(), MinesweeperError> {
+        match format {
+            OutputFormat::Gif if !self.still => self.render_gif(writer),
+            _ => self.render_image(writer, format),
+        }
+    }
+
+    /// Renders the final board as a still image.
+    pub fn render_image<W: Write>(
+        &mut self,
+        mut writer: W,
+        format: OutputFormat,
+    ) -> Result<(), MinesweeperError> {
+        self.replay.seek(i64::MAX);
+
+        let percentage_done = self.replay.board().calculate_done_percentage();
         let frame = self.generate_image(percentage_done)?;
 
-        println!("[{}] 100%", "#".repeat(BAR_LENGTH));
+        if self.progress {
+            eprintln!("[{}] 100%", "#".repeat(BAR_LENGTH));
+        }
 
-        frame
-            .save("output.webp")
-            .map_err(|_| MinesweeperError::ImageSave)
+        let image = DynamicImage::ImageRgba8(frame);
+        let (image, image_format) = match format {
+            OutputFormat::Png => (image, ImageOutputFormat::Png),
+            // JPEG has no alpha channel
+            OutputFormat::Jpeg => (
+                DynamicImage::ImageRgb8(image.to_rgb8()),
+                ImageOutputFormat::Jpeg(90),
+            ),
+            OutputFormat::WebP => (image, ImageOutputFormat::WebP),
+            OutputFormat::Gif => (image, ImageOutputFormat::Gif),
+        };
+
+        // The image encoders need to seek, so encode into memory first
+        let mut encoded = Cursor::new(Vec::new());
+        image
+            .write_to(&mut encoded, image_format)
+            .map_err(|_| MinesweeperError::ImageSave)?;
+
+        writer
+            .write_all(encoded.get_ref())
+            .and_then(|_| writer.flush())
+            .map_err(MinesweeperError::Write)
     }
 
-    pub fn render_gif(&mut self) -> Result<(), MinesweeperError> {
-        let tick_map: BTreeMap<i64, Vec<ActionType>> = self.create_tick_map();
+    /// Renders the game as an animated GIF with one frame per tick.
+    pub fn render_gif<W: Write>(&mut self, writer: W) -> Result<(), MinesweeperError> {
+        let total_ticks = self.replay.len();
         let mut current_image = 0;
 
-        self.encoder =
-            Some(self.create_encoder(self.metadata.x_size as u32, self.metadata.y_size as u32)?);
-
+        // Fail before rendering anything if the frames cannot be encoded
+        let (width, height) = self.image_size();
+        let mut encoder = self.create_encoder(writer, width, height)?;
         let frame = self.generate_image(0)?;
-        self.encode_frame_to_gif(
+
+        self.print_progress(current_image, total_ticks);
+        encode_frame_to_gif(
+            &mut encoder,
             Frame::from_parts(
                 frame,
                 0,
                 0,
                 Delay::from_saturating_duration(Duration::from_secs(1)),
             ),
-            current_image,
-            tick_map.len(),
         )?;
         current_image += 1;
 
-        for (id, tick) in tick_map.iter().enumerate() {
-            let next_tick = tick_map.keys().nth(id + 1);
+        while let Some(state) = self.replay.step_forward() {
+            let tick = state.tick;
+            let id = self.replay.position() - 1;
 
-            let duration = if let Some(next) = next_tick {
-                Duration::from_millis(((next - tick.0) * self.metadata.timeunits as i64) as u64)
+            let duration = if let Some(next) = self.replay.next_tick() {
+                Duration::from_millis(
+                    self.metadata.ticks_to_ms(next.saturating_sub(tick)).max(0) as u64
+                )
             } else {
                 Duration::from_secs(15)
             };
 
-            if tick.1.contains(&ActionType::Flag) {
-                self.flag_data
-                    .iter()
-                    .filter(|flag| flag.total_time.eq(tick.0))
-                    .for_each(|flag| flag.perform_action(&mut self.game_board));
-                //Remove all elements which are less than tick.0
-                self.flag_data.retain(|flag| flag.total_time.gt(tick.0))
-            }
-
-            if tick.1.contains(&ActionType::Open) {
-                self.open_data
-                    .iter()
-                    .filter(|flag| flag.total_time.eq(tick.0))
-                    .for_each(|action| {
-                        self.game_board
-                            .open_field(action.x as usize, action.y as usize);
-                    });
-
-                //Remove all elements which are less than tick.0
-                self.open_data.retain(|open| open.total_time.gt(tick.0))
-            }
-
-            let frame = self.generate_image(if id == (tick_map.len() - 1) {
+            let frame = self.generate_image(if id == (total_ticks - 1) {
                 100
             } else {
-                ((id as f32 / tick_map.len() as f32) * 100.0) as u32
+                ((id as f32 / total_ticks as f32) * 100.0) as u32
             })?;
 
-            self.encode_frame_to_gif(
+            self.print_progress(current_image, total_ticks);
+            encode_frame_to_gif(
+                &mut encoder,
                 Frame::from_parts(frame, 0, 0, Delay::from_saturating_duration(duration)),
-                current_image,
-                tick_map.len(),
             )?;
             current_image += 1;
         }
 
-        print!("\r[{}] 100%", "#".repeat(BAR_LENGTH));
+        if self.progress {
+            eprint!("\r[{}] 100%", "#".repeat(BAR_LENGTH));
+        }
 
-        Ok(())
+        // Writes the GIF trailer
+        encoder
+            .into_inner()
+            .and_then(|mut writer| writer.flush())
+            .map_err(MinesweeperError::Write)
     }
 
-    fn encode_frame_to_gif(
-        &mut self,
-        image: Frame,
-        current_image_id: usize,
-        total_frames: usize,
-    ) -> Result<(), MinesweeperError> {
-        let (width, height) = image.buffer().dimensions();
+    fn print_progress(&self, current_image_id: usize, total_frames: usize) {
+        if !self.progress {
+            return;
+        }
 
         let percent_complete = (current_image_id as f32 / total_frames as f32 * 100.0) as usize;
         let num_hashes = percent_complete * BAR_LENGTH / 100;
-        print!(
+        eprint!(
             "\r[{}{}] {percent_complete:}%",
             "#".repeat(num_hashes),
             " ".repeat(BAR_LENGTH - num_hashes)
         );
-        std::io::stdout().flush().unwrap_or_default();
-
-        let frame_delay = image.delay().numer_denom_ms().0 / 10;
-        let rbga_frame = &mut image.into_buffer();
-        let mut frame = GifFrame::from_rgba_speed(width as u16, height as u16, rbga_frame, 1);
-        frame.delay = frame_delay as u16;
-        frame.dispose = gif::DisposalMethod::Keep;
-
-        self.encoder
-            .as_mut()
-            .unwrap()
-            .write_frame(&frame)
-            .map_err(|_| MinesweeperError::GifEncoding)?;
-
-        Ok(())
+        io::stderr().flush().unwrap_or_default();
     }
 
-    fn create_encoder(
-        &mut self,
+    fn create_encoder<W: Write>(
+        &self,
+        writer: W,
         width: u32,
         height: u32,
-    ) -> Result<Encoder<File>, MinesweeperError> {
-        let mut encoder = Encoder::new(
-            File::create("output.gif").unwrap(),
-            width as u16,
-            height as u16,
-            &[],
-        )
-        .unwrap();
+    ) -> Result<Encoder<W>, MinesweeperError> {
+        let (width, height) = gif_dimensions(width, height)?;
+        let mut encoder =
+            Encoder::new(writer, width, height, &[]).map_err(|_| MinesweeperError::GifEncoding)?;
 
         encoder
-            .set_repeat(if self.options.repeat {
+            .set_repeat(if self.repeat {
                 Repeat::Infinite
             } else {
                 Repeat::Finite(0)
@@ -279,48 +411,36 @@ impl<'a> Renderer<'a> {
         Ok(encoder)
     }
 
-    fn create_tick_map(&mut self) -> BTreeMap<i64, Vec<ActionType>> {
-        let mut tick_map = BTreeMap::new();
-
-        for x in self.open_data.iter() {
-            Self::insert_action(&mut tick_map, x.total_time, ActionType::Open)
-        }
-
-        for x in self.flag_data.iter() {
-            Self::insert_action(&mut tick_map, x.total_time, ActionType::Flag)
-        }
-
-        tick_map
-    }
-
-    fn insert_action(
-        tick_map: &mut BTreeMap<i64, Vec<ActionType>>,
-        total_time: i64,
-        action: ActionType,
-    ) {
-        if let std::collections::btree_map::Entry::Vacant(e) = tick_map.entry(total_time) {
-            e.insert(vec![action]);
-        } else {
-            tick_map.get_mut(&total_time).unwrap().push(action);
-        }
+    /// Width and height of a rendered frame in pixels, the progress bar included.
+    fn image_size(&self) -> (u32, u32) {
+        (
+            self.metadata.x_size as u32 * 32,
+            self.metadata.y_size as u32 * 32 + PROGRESSBAR_HEIGHT,
+        )
     }
 
     fn generate_image(
         &mut self,
         percentage: u32,
     ) -> Result<ImageBuffer<Rgba<u8>, Vec<u8>>, MinesweeperError> {
-        let progressbar_height = 4;
-        let imgx = (self.metadata.x_size * 32) as u32;
-        let imgy = ((self.metadata.y_size * 32) as u32) + progressbar_height;
+        let (imgx, imgy) = self.image_size();
 
         let mut imgbuf = image::ImageBuffer::new(imgx, imgy);
+        let board = self.replay.board();
+        let probabilities = self.heatmap.then(|| mine_probabilities(board));
+        if probabilities.as_ref().is_some_and(|p| !p.exact) {
+            self.estimated_frames += 1;
+        }
 
         for x in 0..self.metadata.x_size as u32 {
             for y in 0..self.metadata.y_size as u32 {
-                let field = &self.game_board.fields[y as usize][x as usize];
+                let coord = Coord::new(x as usize, y as usize);
+                let field = &board.fields[coord];
 
-                // Only render fields that got changed in the last iteration
-                if !self.game_board.changed_fields[y as usize][x as usize] {
+                // Only render fields that got changed in the last iteration, the probabilities
+                // of closed fields can change with every action though
+                let tinted = probabilities.is_some() && field.field_state != FieldState::Open;
+                if !board.changed_fields[coord] && !tinted {
                     continue;
                 }
 
@@ -382,12 +502,18 @@ impl<'a> Renderer<'a> {
                 }
             }
             let pixel_coloring = (percentage * imgx) / 100;
+            // The progress bar turns red once a mine went off
+            let progress_color = if board.exploded.is_some() {
+                Rgba([178, 34, 34, 255])
+            } else {
+                Rgba([103, 149, 60, 255])
+            };
 
             for x in 0..imgx {
-                for y in (imgy - progressbar_height)..imgy {
+                for y in (imgy - PROGRESSBAR_HEIGHT)..imgy {
                     let pixel = imgbuf.get_pixel_mut(x, y);
                     if x <= pixel_coloring {
-                        *pixel = Rgba([103, 149, 60, 255]);
+                        *pixel = progress_color;
                     } else {
                         *pixel = Rgba([0, 0, 0, 255]);
                     }
@@ -395,12 +521,145 @@ impl<'a> Renderer<'a> {
             }
         }
 
+        for (coord, highlight) in self.highlights.iter() {
+            let Some(kind) = highlight else {
+                continue;
+            };
+            if !board.changed_fields[coord] || board.fields[coord].field_state != FieldState::Open {
+                continue;
+            }
+
+            let color = match kind {
+                ClickKind::Blunder => Rgba([220, 20, 60, 255]),
+                _ => Rgba([255, 165, 0, 255]),
+            };
+            draw_outline(&mut imgbuf, coord.x as u32 * 32, coord.y as u32 * 32, color);
+        }
+
+        if let Some(probabilities) = &probabilities {
+            for (coord, probability) in probabilities.fields.iter() {
+                if let Some(probability) = probability {
+                    tint(
+                        &mut imgbuf,
+                        coord.x as u32 * 32,
+                        coord.y as u32 * 32,
+                        *probability,
+                    );
+                }
+            }
+        }
+
         //Reset the changed fields after they got rendered
-        self.game_board
-            .changed_fields
-            .iter_mut()
-            .for_each(|row| row.iter_mut().for_each(|field| *field = false));
+        self.replay.clear_changes();
 
         Ok(imgbuf)
     }
 }
+
+fn encode_frame_to_gif<W: Write>(
+    encoder: &mut Encoder<W>,
+    image: Frame,
+) -> Result<(), MinesweeperError> {
+    let (width, height) = image.buffer().dimensions();
+
+    let frame_delay = image.delay().numer_denom_ms().0 / 10;
+    let (width, height) = gif_dimensions(width, height)?;
+    let rbga_frame = &mut image.into_buffer();
+    let mut frame = GifFrame::from_rgba_speed(width, height, rbga_frame, 1);
+    frame.delay = frame_delay as u16;
+    frame.dispose = gif::DisposalMethod::Keep;
+
+    encoder
+        .write_frame(&frame)
+        .map_err(|_| MinesweeperError::GifEncoding)
+}
+
+/// GIFs store their dimensions as `u16`.
+fn gif_dimensions(width: u32, height: u32) -> Result<(u16, u16), MinesweeperError> {
+    match (u16::try_from(width), u16::try_from(height)) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(MinesweeperError::GifTooLarge { width, height }),
+    }
+}
+
+/// Opens the file at `path` for writing, `-` is stdout.
+fn open_output(path: &str) -> Result<Box<dyn Write>, MinesweeperError> {
+    if path == "-" {
+        return Ok(Box::new(io::stdout()));
+    }
+
+    File::create(path)
+        .map(|file| Box::new(file) as Box<dyn Write>)
+        .map_err(|error| MinesweeperError::OutputCreate(path.to_string(), error))
+}
+
+/// Draws a 2px border around the 32x32 tile at `x`, `y`.
+fn draw_outline(imgbuf: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, x: u32, y: u32, color: Rgba<u8>) {
+    for xd in 0..32 {
+        for yd in 0..32 {
+            if (2..30).contains(&xd) && (2..30).contains(&yd) {
+                continue;
+            }
+            imgbuf.put_pixel(x + xd, y + yd, color);
+        }
+    }
+}
+
+/// Blends the 32x32 tile at `x`, `y` from green for safe to red for a certain mine.
+fn tint(imgbuf: &mut ImageBuffer<Rgba<u8>, Vec<u8>>, x: u32, y: u32, probability: f64) {
+    const OPACITY: f64 = 0.45;
+    let color = [255.0 * probability, 255.0 * (1.0 - probability), 0.0];
+
+    for xd in 0..32 {
+        for yd in 0..32 {
+            let pixel = imgbuf.get_pixel_mut(x + xd, y + yd);
+            for (channel, target) in pixel.0.iter_mut().zip(color) {
+                *channel = (*channel as f64 * (1.0 - OPACITY) + target * OPACITY) as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minesweeper_logic::GameOutcome;
+    use crate::parsers::registry::ParserRegistry;
+    use crate::textures::load_textures;
+
+    #[test]
+    fn renders_non_square_board() {
+        // Expert sized board with a single mine in the bottom right corner
+        let data = ParserRegistry::default()
+            .parse("2=30x16,50+TF+001+")
+            .unwrap();
+        assert_eq!(GameOutcome::of(&data), GameOutcome::Won);
+
+        let sprite = load_textures(None, RenderType::Image).unwrap();
+        let mut renderer = Renderer::new(data, &sprite, false).unwrap();
+        renderer.hide_progress();
+        let png = renderer.render_to_vec(OutputFormat::Png).unwrap();
+
+        let image = image::load_from_memory(&png).unwrap();
+        // The progress bar adds 4 pixels below the board
+        assert_eq!(image.dimensions(), (30 * 32, 16 * 32 + 4));
+    }
+
+    #[test]
+    fn rejects_gif_wider_than_u16() {
+        let data = ParserRegistry::default()
+            .parse("2=2100x1,50+00+A01+")
+            .unwrap();
+        let sprite = load_textures(None, RenderType::Gif).unwrap();
+        let mut renderer = Renderer::new(data, &sprite, false).unwrap();
+        renderer.hide_progress();
+
+        assert!(matches!(
+            renderer.render_to_vec(OutputFormat::Gif),
+            Err(MinesweeperError::GifTooLarge {
+                width: 67200,
+                height: 36
+            })
+        ));
+    }
+}
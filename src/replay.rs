@@ -0,0 +1,317 @@
+use crate::minesweeper_logic::Board;
+use crate::parsers::parser::{FlagAction, OpenAction, ParsedData};
+
+/// A board snapshot is stored every this many ticks to keep seeking cheap.
+const SNAPSHOT_INTERVAL: usize = 32;
+
+#[derive(Clone, Debug)]
+pub enum ReplayAction {
+    Open(OpenAction),
+    Flag(FlagAction),
+    Chord(OpenAction),
+}
+
+impl ReplayAction {
+    /// Ticks since the start of the game.
+    pub fn tick(&self) -> i64 {
+        match self {
+            ReplayAction::Open(open) => open.total_time,
+            ReplayAction::Flag(flag) => flag.total_time,
+            ReplayAction::Chord(chord) => chord.total_time,
+        }
+    }
+
+    pub fn apply(&self, board: &mut Board) {
+        match self {
+            ReplayAction::Open(open) => board.open_field(open.coord()),
+            ReplayAction::Flag(flag) => flag.perform_action(board),
+            ReplayAction::Chord(chord) => board.chord_field(chord.coord()),
+        }
+    }
+}
+
+/// All actions of a game in the order they are replayed in, see [`merge_actions`].
+pub fn actions_in_order(data: &ParsedData) -> Vec<ReplayAction> {
+    merge_actions(&data.open_data, &data.flag_data, &data.chord_data)
+}
+
+/// The actions of a game up to and including the one that won or lost it.
+///
+/// Clients keep recording clicks after the game ended, they do not count towards the game.
+pub fn played_actions(data: &ParsedData) -> Vec<ReplayAction> {
+    let mut board = data.game_board.clone();
+    let mut actions = actions_in_order(data);
+
+    let end = actions.iter().position(|action| {
+        action.apply(&mut board);
+        board.exploded.is_some() || board.is_solved()
+    });
+    if let Some(end) = end {
+        actions.truncate(end + 1);
+    }
+    actions
+}
+
+/// Merges open, flag and chord actions into the order they are replayed in.
+///
+/// Actions are ordered by tick, on the same tick flags come first, then opens and then chords.
+pub fn merge_actions(
+    open_data: &[OpenAction],
+    flag_data: &[FlagAction],
+    chord_data: &[OpenAction],
+) -> Vec<ReplayAction> {
+    let mut actions: Vec<ReplayAction> = flag_data
+        .iter()
+        .cloned()
+        .map(ReplayAction::Flag)
+        .chain(open_data.iter().cloned().map(ReplayAction::Open))
+        .chain(chord_data.iter().cloned().map(ReplayAction::Chord))
+        .collect();
+
+    // The sort is stable, so actions of the same kind keep their order
+    actions.sort_by_key(|action| {
+        let rank = match action {
+            ReplayAction::Flag(_) => 0,
+            ReplayAction::Open(_) => 1,
+            ReplayAction::Chord(_) => 2,
+        };
+        (action.tick(), rank)
+    });
+    actions
+}
+
+/// All actions that happened on the same tick.
+#[derive(Debug)]
+struct TickActions {
+    tick: i64,
+    actions: Vec<ReplayAction>,
+}
+
+/// The board after all actions up to `tick` got applied.
+pub struct ReplayState<'a> {
+    pub tick: i64,
+    /// The actions applied on `tick`, empty for the initial state.
+    pub actions: &'a [ReplayAction],
+    pub board: &'a Board,
+}
+
+impl ReplayState<'_> {
+    /// Copies the state so it outlives the next step of the replay.
+    pub fn snapshot(&self) -> ReplaySnapshot {
+        ReplaySnapshot {
+            tick: self.tick,
+            actions: self.actions.to_vec(),
+            board: self.board.clone(),
+        }
+    }
+}
+
+/// An owned [`ReplayState`], yielded by [`Replay::states`].
+#[derive(Clone, Debug)]
+pub struct ReplaySnapshot {
+    pub tick: i64,
+    pub actions: Vec<ReplayAction>,
+    pub board: Board,
+}
+
+/// Iterator over every state of a replay, see [`Replay::states`].
+pub struct States<'a> {
+    replay: &'a mut Replay,
+    started: bool,
+}
+
+impl Iterator for States<'_> {
+    type Item = ReplaySnapshot;
+
+    fn next(&mut self) -> Option<ReplaySnapshot> {
+        if !self.started {
+            self.started = true;
+            return Some(self.replay.current().snapshot());
+        }
+        self.replay.step_forward().map(|state| state.snapshot())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.replay.len() - self.replay.position() + usize::from(!self.started);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for States<'_> {}
+
+/// Steps through a game tick by tick.
+///
+/// Actions sharing a tick are applied together, flags before opens. The replay starts at the
+/// initial state before any action, which reports tick `0`.
+pub struct Replay {
+    timeline: Vec<TickActions>,
+    board: Board,
+    /// Number of ticks of the timeline applied to `board`.
+    position: usize,
+    /// `snapshots[i]` is the board after `i * SNAPSHOT_INTERVAL` ticks.
+    snapshots: Vec<Board>,
+}
+
+impl Replay {
+    pub fn new(data: ParsedData) -> Self {
+        let mut timeline: Vec<TickActions> = Vec::new();
+
+        for action in actions_in_order(&data) {
+            match timeline.last_mut() {
+                Some(last) if last.tick == action.tick() => last.actions.push(action),
+                _ => timeline.push(TickActions {
+                    tick: action.tick(),
+                    actions: vec![action],
+                }),
+            }
+        }
+
+        let mut board = data.game_board;
+        let mut snapshots = vec![board.clone()];
+        for (position, tick) in timeline.iter().enumerate() {
+            apply(&mut board, &tick.actions);
+            if (position + 1) % SNAPSHOT_INTERVAL == 0 {
+                snapshots.push(board.clone());
+            }
+        }
+
+        let mut replay = Replay {
+            timeline,
+            board: snapshots[0].clone(),
+            position: 0,
+            snapshots,
+        };
+        replay.board.changed_fields.fill(true);
+        replay
+    }
+
+    /// Number of ticks on which at least one action happened.
+    pub fn len(&self) -> usize {
+        self.timeline.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timeline.is_empty()
+    }
+
+    /// Number of ticks applied so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// All ticks on which an action happened, in order.
+    pub fn ticks(&self) -> impl Iterator<Item = i64> + '_ {
+        self.timeline.iter().map(|tick| tick.tick)
+    }
+
+    /// The tick the next call to [`Replay::step_forward`] will apply.
+    pub fn next_tick(&self) -> Option<i64> {
+        self.timeline.get(self.position).map(|tick| tick.tick)
+    }
+
+    pub fn current(&self) -> ReplayState<'_> {
+        match self.position.checked_sub(1) {
+            Some(last) => ReplayState {
+                tick: self.timeline[last].tick,
+                actions: &self.timeline[last].actions,
+                board: &self.board,
+            },
+            None => ReplayState {
+                tick: 0,
+                actions: &[],
+                board: &self.board,
+            },
+        }
+    }
+
+    /// Applies the next tick, returns `None` once the end of the game is reached.
+    pub fn step_forward(&mut self) -> Option<ReplayState<'_>> {
+        let tick = self.timeline.get(self.position)?;
+        apply(&mut self.board, &tick.actions);
+        self.position += 1;
+        Some(self.current())
+    }
+
+    /// Goes back one tick, returns `None` at the initial state.
+    pub fn step_back(&mut self) -> Option<ReplayState<'_>> {
+        let position = self.position.checked_sub(1)?;
+        self.restore(position);
+        Some(self.current())
+    }
+
+    /// Moves to the state after every action up to and including `tick`.
+    pub fn seek(&mut self, tick: i64) -> ReplayState<'_> {
+        let position = self.timeline.partition_point(|entry| entry.tick <= tick);
+        self.restore(position);
+        self.current()
+    }
+
+    /// Iterates over every state of the game in order, starting with the initial state.
+    ///
+    /// The replay is rewound first and ends up at the last state. Every state is a copy of the
+    /// board, use [`Replay::step_forward`] to walk through a game without copying.
+    pub fn states(&mut self) -> States<'_> {
+        self.restore(0);
+        States {
+            replay: self,
+            started: false,
+        }
+    }
+
+    /// Marks every field as rendered, see [`Board::changed_fields`].
+    pub fn clear_changes(&mut self) {
+        self.board.changed_fields.fill(false);
+    }
+
+    fn restore(&mut self, position: usize) {
+        if position < self.position {
+            let snapshot = position / SNAPSHOT_INTERVAL;
+            self.board = self.snapshots[snapshot].clone();
+            self.position = snapshot * SNAPSHOT_INTERVAL;
+        }
+
+        while self.position < position {
+            apply(&mut self.board, &self.timeline[self.position].actions);
+            self.position += 1;
+        }
+
+        // The whole board has to be redrawn after jumping around
+        self.board.changed_fields.fill(true);
+    }
+}
+
+fn apply(board: &mut Board, actions: &[ReplayAction]) {
+    for action in actions {
+        action.apply(board);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::registry::ParserRegistry;
+
+    #[test]
+    fn states_visit_every_tick() {
+        let data = ParserRegistry::default()
+            .parse("2=4x2,50+0010+111;211;301+")
+            .unwrap();
+        let mut replay = Replay::new(data);
+        let ticks = replay.len();
+        replay.seek(2);
+
+        let states: Vec<(i64, usize, u32)> = replay
+            .states()
+            .map(|state| (state.tick, state.actions.len(), state.board.open_fields))
+            .collect();
+
+        assert_eq!(states.len(), ticks + 1);
+        assert_eq!(states[0], (0, 0, 0));
+        assert!(states.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(replay.position(), ticks);
+    }
+}
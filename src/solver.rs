@@ -0,0 +1,238 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::grid::{Coord, Grid};
+use crate::minesweeper_logic::{Board, FieldState};
+use crate::parsers::parser::ParsedData;
+use crate::probability::mine_probabilities;
+use crate::replay::{actions_in_order, ReplayAction};
+
+/// Rounding errors of the probabilities that still count as 0 or 1.
+const PROOF_TOLERANCE: f64 = 1e-9;
+
+/// What is known about a field from the numbers visible on the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deduced {
+    Unknown,
+    Safe,
+    Mine,
+}
+
+/// How a click on a field can be judged with the information the player had at that time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClickKind {
+    /// The field was provably safe.
+    Forced,
+    /// Neither safe nor a mine could be proven.
+    Guess,
+    /// The field was provably a mine.
+    Blunder,
+}
+
+impl fmt::Display for ClickKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ClickKind::Forced => "forced",
+            ClickKind::Guess => "guess",
+            ClickKind::Blunder => "blunder",
+        })
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ClickAnalysis {
+    pub coord: Coord,
+    pub tick: i64,
+    pub kind: ClickKind,
+}
+
+/// Classifies every open action of a game as forced, guess or blunder.
+///
+/// Fields the simple rules of [`deduce`] cannot decide are checked against every consistent mine
+/// layout, see [`mine_probabilities`]. Only an exact probability of 0 or 1 counts as proof.
+pub fn analyse(data: &ParsedData) -> Vec<ClickAnalysis> {
+    let mut board = data.game_board.clone();
+    let mut analysis = Vec::new();
+
+    for action in actions_in_order(data) {
+        if let ReplayAction::Open(open) = &action {
+            let coord = open.coord();
+            let kind = match deduce(&board)[coord] {
+                Deduced::Safe => ClickKind::Forced,
+                Deduced::Mine => ClickKind::Blunder,
+                Deduced::Unknown => classify(&board, coord),
+            };
+
+            analysis.push(ClickAnalysis {
+                coord,
+                tick: open.total_time,
+                kind,
+            });
+        }
+
+        action.apply(&mut board);
+    }
+
+    analysis
+}
+
+/// Judges a field by the probability of it being a mine over all consistent layouts.
+fn classify(board: &Board, coord: Coord) -> ClickKind {
+    let probabilities = mine_probabilities(board);
+    if !probabilities.exact {
+        return ClickKind::Guess;
+    }
+
+    match probabilities.fields[coord] {
+        Some(probability) if probability <= PROOF_TOLERANCE => ClickKind::Forced,
+        Some(probability) if probability >= 1.0 - PROOF_TOLERANCE => ClickKind::Blunder,
+        _ => ClickKind::Guess,
+    }
+}
+
+/// Fields of a closed area that contain exactly `mines` mines.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Constraint {
+    pub(crate) cells: Vec<Coord>,
+    pub(crate) mines: usize,
+}
+
+/// Deduces which closed fields are safe or mines from the visible numbers and the total mine
+/// count. Flags are ignored since they are only the players guess.
+pub fn deduce(board: &Board) -> Grid<Deduced> {
+    let fields = &board.fields;
+    let mut known = Grid::new(fields.width(), fields.height(), Deduced::Unknown);
+    for (coord, field) in fields.iter() {
+        if field.field_state == FieldState::Open {
+            known[coord] = Deduced::Safe;
+        }
+    }
+
+    loop {
+        let constraints = constraints(board, &known);
+        let mut changed = false;
+
+        for constraint in &constraints {
+            if constraint.mines == 0 {
+                changed |= mark(&mut known, &constraint.cells, Deduced::Safe);
+            } else if constraint.mines == constraint.cells.len() {
+                changed |= mark(&mut known, &constraint.cells, Deduced::Mine);
+            }
+        }
+        if changed {
+            continue;
+        }
+
+        // If one constraint is part of another the remaining cells hold the difference
+        for small in &constraints {
+            for big in &constraints {
+                if small.cells.len() >= big.cells.len()
+                    || !small.cells.iter().all(|c| big.cells.contains(c))
+                {
+                    continue;
+                }
+
+                let rest: Vec<Coord> = big
+                    .cells
+                    .iter()
+                    .filter(|c| !small.cells.contains(c))
+                    .copied()
+                    .collect();
+                let mines = big.mines.saturating_sub(small.mines);
+
+                if mines == 0 {
+                    changed |= mark(&mut known, &rest, Deduced::Safe);
+                } else if mines == rest.len() {
+                    changed |= mark(&mut known, &rest, Deduced::Mine);
+                }
+            }
+        }
+        if changed {
+            continue;
+        }
+
+        let unknown: Vec<Coord> = known
+            .iter()
+            .filter(|(_, &deduced)| deduced == Deduced::Unknown)
+            .map(|(coord, _)| coord)
+            .collect();
+        let found_mines = known.iter().filter(|(_, &d)| d == Deduced::Mine).count();
+        let remaining_mines = (board.mine_count as usize).saturating_sub(found_mines);
+
+        if !unknown.is_empty() && remaining_mines == 0 {
+            mark(&mut known, &unknown, Deduced::Safe);
+        } else if !unknown.is_empty() && remaining_mines == unknown.len() {
+            mark(&mut known, &unknown, Deduced::Mine);
+        }
+
+        return known;
+    }
+}
+
+/// One constraint per open number that still borders unknown fields.
+pub(crate) fn constraints(board: &Board, known: &Grid<Deduced>) -> Vec<Constraint> {
+    let fields = &board.fields;
+    let mut constraints = Vec::new();
+
+    for (coord, field) in fields.iter() {
+        if field.field_state != FieldState::Open || field.mine {
+            continue;
+        }
+
+        let mut cells = Vec::new();
+        let mut mines = 0;
+        for neighbour in fields.neighbours(coord) {
+            match known[neighbour] {
+                Deduced::Unknown => cells.push(neighbour),
+                Deduced::Mine => mines += 1,
+                Deduced::Safe => {}
+            }
+        }
+
+        if !cells.is_empty() {
+            cells.sort();
+            constraints.push(Constraint {
+                cells,
+                mines: (field.value as usize).saturating_sub(mines),
+            });
+        }
+    }
+
+    constraints.sort();
+    constraints.dedup();
+    constraints
+}
+
+fn mark(known: &mut Grid<Deduced>, cells: &[Coord], value: Deduced) -> bool {
+    let mut changed = false;
+
+    for &cell in cells {
+        if known[cell] == Deduced::Unknown {
+            known[cell] = value;
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::registry::ParserRegistry;
+
+    #[test]
+    fn global_mine_count_proves_safe_click() {
+        let data = ParserRegistry::default()
+            .parse("2=4x2,50+0010+111;211;301+")
+            .unwrap();
+        let kinds: Vec<ClickKind> = analyse(&data).iter().map(|click| click.kind).collect();
+
+        assert_eq!(
+            kinds,
+            [ClickKind::Guess, ClickKind::Guess, ClickKind::Forced]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use serde::Serialize;
+
+use crate::grid::{Coord, Grid};
+use crate::minesweeper_logic::{Board, FieldState};
+use crate::parsers::parser::ParsedData;
+use crate::replay::{played_actions, ReplayAction};
+
+/// The community standard metrics of a single game.
+///
+/// Rates are based on the solved 3BV so they are meaningful for lost games as well. Clicks after
+/// the game was won or lost are not counted, see [`played_actions`].
+#[derive(Clone, Debug, Serialize)]
+pub struct Statistics {
+    /// Minimum number of left clicks needed to solve the board.
+    pub bbbv: u32,
+    /// 3BV that got cleared during the game.
+    pub solved_bbbv: u32,
+    pub bbbv_per_second: f64,
+    /// Connected areas of fields without surrounding mines.
+    pub openings: u32,
+    /// Connected groups of numbered fields that do not border an opening.
+    pub islands: u32,
+    pub clicks: u32,
+    pub left_clicks: u32,
+    pub right_clicks: u32,
+    /// Chords, counted as a single click each.
+    pub double_clicks: u32,
+    /// Clicks that changed the board, see [`Statistics::correctness`].
+    pub effective_clicks: u32,
+    /// Solved 3BV per click in percent.
+    pub efficiency: f64,
+    /// Index of efficiency, solved 3BV per click.
+    pub ioe: f64,
+    /// Rapport qualité prix, time divided by 3BV/s. Lower is better.
+    pub rqp: f64,
+    /// Share of clicks that opened a field or changed a flag.
+    pub correctness: f64,
+    /// Time of the action that ended the game, or of the last action, in milliseconds.
+    pub time_ms: i64,
+}
+
+impl Statistics {
+    pub fn of(data: &ParsedData) -> Statistics {
+        let regions = Regions::of(&data.game_board);
+        let mut board = data.game_board.clone();
+
+        let mut left_clicks = 0;
+        let mut right_clicks = 0;
+        let mut double_clicks = 0;
+        let mut effective_clicks = 0;
+        let mut last_tick = 0;
+
+        for action in played_actions(data) {
+            let before = (board.open_fields, field_state(&board, &action));
+            action.apply(&mut board);
+            if before != (board.open_fields, field_state(&board, &action)) {
+                effective_clicks += 1;
+            }
+
+            match action {
+                ReplayAction::Open(_) => left_clicks += 1,
+                ReplayAction::Flag(_) => right_clicks += 1,
+                ReplayAction::Chord(_) => double_clicks += 1,
+            }
+            last_tick = action.tick();
+        }
+
+        let solved_bbbv = regions.solved(&board);
+        let clicks = left_clicks + right_clicks + double_clicks;
+        let time_ms = data.metadata.ticks_to_ms(last_tick);
+        let seconds = time_ms as f64 / 1000.0;
+        let bbbv_per_second = ratio(solved_bbbv as f64, seconds);
+
+        Statistics {
+            bbbv: regions.bbbv(),
+            solved_bbbv,
+            bbbv_per_second,
+            openings: regions.openings.len() as u32,
+            islands: regions.islands,
+            clicks,
+            left_clicks,
+            right_clicks,
+            double_clicks,
+            effective_clicks,
+            efficiency: ratio(solved_bbbv as f64, clicks as f64) * 100.0,
+            ioe: ratio(solved_bbbv as f64, clicks as f64),
+            rqp: ratio(seconds, bbbv_per_second),
+            correctness: ratio(effective_clicks as f64, clicks as f64),
+            time_ms,
+        }
+    }
+}
+
+fn field_state(board: &Board, action: &ReplayAction) -> Option<FieldState> {
+    match action {
+        ReplayAction::Open(_) | ReplayAction::Chord(_) => None,
+        ReplayAction::Flag(flag) => board
+            .fields
+            .get(flag.coord())
+            .map(|f| f.field_state.clone()),
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+/// The 3BV relevant areas of a board.
+struct Regions {
+    /// The empty fields of every opening.
+    openings: Vec<Vec<Coord>>,
+    /// Numbered fields that are not revealed by any opening.
+    isolated: Vec<Coord>,
+    islands: u32,
+}
+
+impl Regions {
+    fn of(board: &Board) -> Regions {
+        let fields = &board.fields;
+        let is_empty = |coord: Coord| !fields[coord].mine && fields[coord].value == 0;
+
+        let mut openings = Vec::new();
+        let mut seen = Grid::new(fields.width(), fields.height(), false);
+        for coord in fields.coords() {
+            if is_empty(coord) && !seen[coord] {
+                openings.push(flood_fill(fields, &mut seen, coord, is_empty));
+            }
+        }
+
+        let is_isolated = |coord: Coord| {
+            !fields[coord].mine
+                && fields[coord].value > 0
+                && !fields.neighbours(coord).any(is_empty)
+        };
+        let isolated: Vec<Coord> = fields.coords().filter(|&c| is_isolated(c)).collect();
+
+        let mut islands = 0;
+        let mut seen = Grid::new(fields.width(), fields.height(), false);
+        for &coord in &isolated {
+            if !seen[coord] {
+                flood_fill(fields, &mut seen, coord, is_isolated);
+                islands += 1;
+            }
+        }
+
+        Regions {
+            openings,
+            isolated,
+            islands,
+        }
+    }
+
+    fn bbbv(&self) -> u32 {
+        (self.openings.len() + self.isolated.len()) as u32
+    }
+
+    fn solved(&self, board: &Board) -> u32 {
+        let is_open = |coord: &Coord| board.fields[*coord].field_state == FieldState::Open;
+
+        let openings = self
+            .openings
+            .iter()
+            .filter(|opening| opening.iter().any(is_open))
+            .count();
+        let isolated = self.isolated.iter().filter(|c| is_open(c)).count();
+
+        (openings + isolated) as u32
+    }
+}
+
+/// Collects all fields connected to `start` that match `include`.
+fn flood_fill<T>(
+    grid: &Grid<T>,
+    seen: &mut Grid<bool>,
+    start: Coord,
+    include: impl Fn(Coord) -> bool,
+) -> Vec<Coord> {
+    let mut region = Vec::new();
+    let mut stack = vec![start];
+    seen[start] = true;
+
+    while let Some(coord) = stack.pop() {
+        region.push(coord);
+        for neighbour in grid.neighbours(coord) {
+            if !seen[neighbour] && include(neighbour) {
+                seen[neighbour] = true;
+                stack.push(neighbour);
+            }
+        }
+    }
+
+    region
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::minesweeper_logic::GameOutcome;
+    use crate::parsers::registry::ParserRegistry;
+
+    // 8x1 board with mines on 2 and 5: openings on 0 and 7, one island of the numbers on 3 and 4
+    const GAME: &str = "2=8x1,50+2050+001;701;305;40A";
+
+    #[test]
+    fn counts_openings_and_islands() {
+        let data = ParserRegistry::default()
+            .parse(&format!("{GAME}+"))
+            .unwrap();
+        let statistics = Statistics::of(&data);
+
+        assert_eq!(statistics.openings, 2);
+        assert_eq!(statistics.islands, 1);
+        assert_eq!(statistics.bbbv, 4);
+        assert_eq!(statistics.solved_bbbv, 4);
+        assert_eq!(statistics.clicks, 4);
+        assert_eq!(statistics.time_ms, 850);
+    }
+
+    #[test]
+    fn ignores_clicks_after_the_game_ended() {
+        // Opens a mine and flags a field long after the game was won
+        let data = ParserRegistry::default()
+            .parse(&format!("{GAME};20z+50zP"))
+            .unwrap();
+        let statistics = Statistics::of(&data);
+
+        assert_eq!(statistics.clicks, 4);
+        assert_eq!(statistics.right_clicks, 0);
+        assert_eq!(statistics.time_ms, 850);
+        assert!((statistics.bbbv_per_second - 4.0 / 0.85).abs() < 1e-9);
+        assert_eq!(GameOutcome::of(&data), GameOutcome::Won);
+    }
+}
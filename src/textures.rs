@@ -1,31 +1,29 @@
-use crate::parsers::parser::Metadata;
-use crate::renderer::{RenderOptions, RenderType};
+use crate::error::MinesweeperError;
+use crate::renderer::RenderType;
 use std::fs;
-use std::fs::File;
-use std::io::Read;
 
-pub fn load_textures(options: &RenderOptions, metadata: &Metadata) -> Vec<u8> {
+/// Returns the texture sheet to render with.
+///
+/// A custom texture file takes precedence, otherwise the bundled sheet matching the render
+/// type is used.
+pub fn load_textures(
+    custom_textures: Option<&str>,
+    render_type: RenderType,
+) -> Result<Vec<u8>, MinesweeperError> {
     let skin_full: Vec<u8> = include_bytes!("../resources/skin_full.png").to_vec();
     let skin_gif: Vec<u8> = include_bytes!("../resources/skin_20.png").to_vec();
 
-    if let Some(path) = &options.custom_textures {
+    if let Some(path) = custom_textures {
         load_file_data(path)
-    } else if let Some(t) = &options.force_type {
-        match t {
-            RenderType::Image => skin_full,
-            RenderType::Gif => skin_gif,
-        }
-    } else if metadata.x_size >= 32 || metadata.y_size >= 32 {
-        skin_full
     } else {
-        skin_gif
+        match render_type {
+            RenderType::Image => Ok(skin_full),
+            RenderType::Gif => Ok(skin_gif),
+        }
     }
 }
 
-fn load_file_data(custom_texture_sprite: &str) -> Vec<u8> {
-    let mut file = File::open(custom_texture_sprite).expect("no file found");
-    let metadata = fs::metadata(custom_texture_sprite).expect("unable to read metadata");
-    let mut buffer = vec![0; metadata.len() as usize];
-    file.read_exact(&mut buffer).expect("buffer overflow");
-    buffer
+fn load_file_data(custom_texture_sprite: &str) -> Result<Vec<u8>, MinesweeperError> {
+    fs::read(custom_texture_sprite)
+        .map_err(|error| MinesweeperError::TextureLoad(custom_texture_sprite.to_string(), error))
 }
@@ -0,0 +1,52 @@
+use crate::error::MinesweeperError;
+use crate::parsers::registry::{split_version, ParserRegistry};
+use crate::parsers::serializer::Iserializer;
+use crate::parsers::v2::serializer::SerializerV2;
+
+/// Rewrites a version 1 game as version 2, games of other versions are returned unchanged.
+///
+/// Version 1 stores decimal ticks of a fixed 50ms, version 2 writes the same ticks in base62
+/// together with the timeunits, so every action keeps its exact time. The result is parsed again
+/// and has to match the original game.
+pub fn upgrade_v1(parsers: &ParserRegistry, data: &str) -> Result<String, MinesweeperError> {
+    let data = data.trim();
+    let (version, _) = split_version(data)?;
+    if version != "1" {
+        return Ok(data.to_string());
+    }
+
+    let parsed = parsers.parse(data)?;
+    let upgraded = SerializerV2.serialize(&parsed)?;
+    if parsers.parse(&upgraded)? != parsed {
+        return Err(MinesweeperError::Mismatch {
+            from: version.to_string(),
+            to: SerializerV2.version().to_string(),
+        });
+    }
+
+    Ok(upgraded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrades_version_1_with_the_same_ticks() {
+        let parsers = ParserRegistry::default();
+        let game = "1=4x2+0010+1112;2130+0012P";
+        let upgraded = upgrade_v1(&parsers, game).unwrap();
+
+        assert_eq!(upgraded, "2=4x2,50+0010+11C;21U+00CP");
+        assert_eq!(
+            parsers.parse(&upgraded).unwrap(),
+            parsers.parse(game).unwrap()
+        );
+    }
+
+    #[test]
+    fn keeps_other_versions() {
+        let game = "2=4x2,50+0010+11A;21B+00CP";
+        assert_eq!(upgrade_v1(&ParserRegistry::default(), game).unwrap(), game);
+    }
+}
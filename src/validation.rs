@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::error::Section;
+use crate::grid::Coord;
+use crate::minesweeper_logic::{Board, FieldState};
+use crate::parsers::parser::{Action, ParsedData, RawGame};
+use crate::parsers::registry::ParserRegistry;
+use crate::replay::{merge_actions, ReplayAction};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Unusual but the game can still be replayed.
+    Warning,
+    /// The game cannot be replayed as it is.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A single problem found in a game.
+#[derive(Clone, Debug, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    /// The section and entry index the issue belongs to, if any.
+    pub location: Option<(Section, usize)>,
+    pub message: String,
+}
+
+impl Issue {
+    fn new(severity: Severity, location: Option<(Section, usize)>, message: String) -> Self {
+        Issue {
+            severity,
+            location,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some((section, index)) => {
+                write!(
+                    f,
+                    "{}: {section} entry {index}: {}",
+                    self.severity, self.message
+                )
+            }
+            None => write!(f, "{}: {}", self.severity, self.message),
+        }
+    }
+}
+
+/// Checks a game string for problems, an empty list means the game is fine.
+///
+/// Unlike [`ParserRegistry::parse`] this does not stop at the first invalid mine or action. Only
+/// games that cannot be parsed at all are reported with a single error.
+pub fn validate(registry: &ParserRegistry, data: &str) -> Vec<Issue> {
+    match registry.parse_raw(data) {
+        Ok(game) => validate_game(&game),
+        Err(error) => vec![Issue::new(Severity::Error, None, error.to_string())],
+    }
+}
+
+/// Checks an already built game, like one imported from another client.
+pub fn validate_parsed(data: &ParsedData) -> Vec<Issue> {
+    validate_game(&RawGame {
+        metadata: data.metadata.clone(),
+        mines: data
+            .game_board
+            .fields
+            .iter()
+            .filter(|(_, field)| field.mine)
+            .map(|(coord, _)| (coord.x as i32, coord.y as i32))
+            .collect(),
+        open_data: data.open_data.clone(),
+        flag_data: data.flag_data.clone(),
+        chord_data: data.chord_data.clone(),
+    })
+}
+
+fn validate_game(game: &RawGame) -> Vec<Issue> {
+    let metadata = &game.metadata;
+    let mut issues = Vec::new();
+    let mut board = Board::new(metadata, &[]);
+
+    if metadata.timeunits <= 0 {
+        issues.push(Issue::new(
+            Severity::Error,
+            None,
+            format!("timeunits {} must be positive", metadata.timeunits),
+        ));
+    }
+
+    let mut mines = HashSet::new();
+    for (index, &(x, y)) in game.mines.iter().enumerate() {
+        let location = Some((Section::Mines, index));
+        match board.fields.coord(x as i64, y as i64) {
+            None => issues.push(Issue::new(
+                Severity::Error,
+                location,
+                format!("mine {x}|{y} is outside of the board"),
+            )),
+            Some(coord) if !mines.insert(coord) => issues.push(Issue::new(
+                Severity::Warning,
+                location,
+                format!("mine {x}|{y} is listed twice, it is only placed once"),
+            )),
+            Some(_) => {}
+        }
+    }
+
+    let fields = board.total_fields as usize;
+    if mines.is_empty() {
+        issues.push(Issue::new(
+            Severity::Warning,
+            None,
+            "the board has no mines".to_string(),
+        ));
+    } else if mines.len() >= fields {
+        issues.push(Issue::new(
+            Severity::Error,
+            None,
+            format!(
+                "{} mines leave no safe field on the {}x{} board",
+                mines.len(),
+                metadata.x_size,
+                metadata.y_size
+            ),
+        ));
+    }
+
+    let mines: Vec<Coord> = mines.into_iter().collect();
+    board = Board::new(metadata, &mines);
+
+    let mut opens = 0;
+    let mut flags = 0;
+    let mut chords = 0;
+    let mut ended = false;
+    for action in merge_actions(&game.open_data, &game.flag_data, &game.chord_data) {
+        let (location, x, y) = match &action {
+            ReplayAction::Open(open) => {
+                opens += 1;
+                ((Section::Opens, opens - 1), open.x, open.y)
+            }
+            ReplayAction::Flag(flag) => {
+                flags += 1;
+                ((Section::Flags, flags - 1), flag.x, flag.y)
+            }
+            ReplayAction::Chord(chord) => {
+                chords += 1;
+                ((Section::Chords, chords - 1), chord.x, chord.y)
+            }
+        };
+        let issue = |severity, message: &str| {
+            Issue::new(severity, Some(location), format!("{x}|{y} {message}"))
+        };
+
+        let Some(coord) = board.fields.coord(x as i64, y as i64) else {
+            issues.push(issue(Severity::Error, "is outside of the board"));
+            continue;
+        };
+
+        if ended {
+            issues.push(issue(
+                Severity::Warning,
+                &format!("happens on tick {} after the game ended", action.tick()),
+            ));
+        }
+
+        let state = &board.fields[coord].field_state;
+        match &action {
+            ReplayAction::Open(_) if *state == FieldState::Flagged => {
+                issues.push(issue(Severity::Warning, "opens a flagged field"));
+            }
+            ReplayAction::Flag(flag)
+                if matches!(flag.action, Action::Remove)
+                    && !matches!(state, FieldState::Flagged | FieldState::UnsureFlagged) =>
+            {
+                issues.push(issue(Severity::Warning, "removes a flag that is not there"));
+            }
+            _ => {}
+        }
+
+        action.apply(&mut board);
+        ended |= board.exploded.is_some() || board.is_solved();
+    }
+
+    issues
+}